@@ -1,112 +1,302 @@
 use super::operation::{OpCode, OperationBase};
+use crate::value::bigint::BigInt;
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::rc::Rc;
 
+/// Integer results are capped at this many bits, so a single `**` or `<<` can't exhaust memory.
+const MAX_RESULT_BITS: u64 = 1 << 20;
+
 #[derive(Debug)]
 pub enum BinaryOp {
     Add,
     Subtract,
     Mult,
     Div,
+    IntDiv,
     Modulo,
+    Pow,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Equal,
     Greater,
     Less,
 }
 
+/// Numeric operands after promotion: integers stay integers, widening to big
+/// integers if either one is big, but mixing in a float makes both floats.
+enum Operands {
+    Int(i64, i64),
+    Big(BigInt, BigInt),
+    Float(f64, f64),
+}
+
+impl Operands {
+    fn promote(a: &Value, b: &Value) -> Option<Self> {
+        match (a, b) {
+            (Value::Int(a), Value::Int(b)) => return Some(Self::Int(*a, *b)),
+            (Value::Number(_), _) | (_, Value::Number(_)) => {
+                return Some(Self::Float(Self::to_f64(a)?, Self::to_f64(b)?));
+            }
+            _ => return Some(Self::Big(Self::to_big(a)?, Self::to_big(b)?)),
+        }
+    }
+
+    fn to_f64(value: &Value) -> Option<f64> {
+        match value {
+            Value::Number(num) => return Some(*num),
+            Value::Int(num) => return Some(*num as f64),
+            Value::BigInt(num) => return Some(num.to_f64()),
+            _ => return None,
+        }
+    }
+
+    /// Orders an integer against a float exactly, since converting a large integer to `f64` can round it.
+    fn compare_mixed(a: &Value, b: &Value) -> Option<Ordering> {
+        match (a, b) {
+            (Value::Number(a), b) => {
+                return Some(Self::compare_int(&Self::to_big(b)?, *a)?.reverse())
+            }
+            (a, Value::Number(b)) => return Self::compare_int(&Self::to_big(a)?, *b),
+            _ => return None,
+        }
+    }
+
+    fn compare_int(int: &BigInt, float: f64) -> Option<Ordering> {
+        if float.is_nan() {
+            return None;
+        }
+        if float.is_infinite() {
+            return Some(if float > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+        let floor = float.floor();
+        // past the floor of a fractional float means at least its ceiling
+        match int.cmp(&BigInt::from_f64(floor)?) {
+            Ordering::Equal if floor != float => return Some(Ordering::Less),
+            ordering => return Some(ordering),
+        }
+    }
+
+    fn to_big(value: &Value) -> Option<BigInt> {
+        match value {
+            Value::Int(num) => return Some(BigInt::from_i64(*num)),
+            Value::BigInt(num) => return Some(num.as_ref().clone()),
+            _ => return None,
+        }
+    }
+}
+
 impl BinaryOp {
     pub fn eval(&self, a: Value, b: Value, line: usize) -> Result<Value, QalamError> {
-        match self {
-            Self::Add => {
-                if let (Value::Number(a), Value::Number(b)) = (a.clone(), b.clone()) {
-                    return Ok(Value::Number(a + b));
-                } else if let (Value::String(a), Value::String(b)) = (a, b) {
-                    return Ok(Value::String(a + &b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be 2 numbers or 2 strings!",
-                        line,
-                    ));
-                }
+        match (self, &a, &b) {
+            (Self::Equal, _, _) => return Ok(Value::Bool(a == b)),
+            (Self::Add, Value::String(a), Value::String(b)) => {
+                return Ok(Value::String(a.clone() + b));
             }
-            Self::Subtract => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    return Ok(Value::Number(a - b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
-                }
+            (Self::Greater | Self::Less, Value::Number(_), Value::Int(_) | Value::BigInt(_))
+            | (Self::Greater | Self::Less, Value::Int(_) | Value::BigInt(_), Value::Number(_)) => {
+                let expected = match self {
+                    Self::Greater => Ordering::Greater,
+                    _ => Ordering::Less,
+                };
+                return Ok(Value::Bool(
+                    Operands::compare_mixed(&a, &b) == Some(expected),
+                ));
             }
-            Self::Mult => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    return Ok(Value::Number(a * b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
-                }
+            _ => {}
+        }
+        match Operands::promote(&a, &b) {
+            Some(Operands::Int(a, b)) => return self.eval_int(a, b, line),
+            Some(Operands::Big(a, b)) => return self.eval_big(a, b, line),
+            Some(Operands::Float(a, b)) => return self.eval_float(a, b, line),
+            None => {
+                let message = match self {
+                    Self::Add => "Operands must be 2 numbers or 2 strings!",
+                    _ if self.is_bitwise() => "Operands must be integers!",
+                    _ => "Operands must be numbers!",
+                };
+                return Err(QalamError::with_line_runtime(message, line));
             }
-            Self::Div => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    if b == 0.0 {
-                        return Err(QalamError::with_line_runtime(
-                            "Cannot divide by zero!",
-                            line,
-                        ));
-                    }
-                    return Ok(Value::Number(a / b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
+        }
+    }
+
+    fn is_division(&self) -> bool {
+        return matches!(self, Self::Div | Self::IntDiv | Self::Modulo);
+    }
+
+    fn is_bitwise(&self) -> bool {
+        return matches!(
+            self,
+            Self::BitAnd | Self::BitOr | Self::BitXor | Self::ShiftLeft | Self::ShiftRight
+        );
+    }
+
+    /// The shift amount as a `u32`, saturating larger ones since that's already past every bit of a value that fits in
+    /// memory.
+    fn shift_amount(shift: i64, line: usize) -> Result<u32, QalamError> {
+        if shift < 0 {
+            return Err(QalamError::with_line_runtime(
+                "Shift amount must not be negative.",
+                line,
+            ));
+        }
+        return Ok(u32::try_from(shift).unwrap_or(u32::MAX));
+    }
+
+    fn eval_int(&self, a: i64, b: i64, line: usize) -> Result<Value, QalamError> {
+        if b == 0 && self.is_division() {
+            return Err(QalamError::with_line_runtime(
+                "Cannot divide by zero!",
+                line,
+            ));
+        }
+        let result = match self {
+            Self::Add => a.checked_add(b),
+            Self::Subtract => a.checked_sub(b),
+            Self::Mult => a.checked_mul(b),
+            // `/` is always true division, `~/` keeps integers
+            Self::Div => return Ok(Value::Number(a as f64 / b as f64)),
+            Self::IntDiv => a.checked_div(b),
+            Self::Modulo => a.checked_rem(b),
+            Self::Pow => {
+                if b < 0 {
+                    return Ok(Value::Number((a as f64).powf(b as f64)));
                 }
+                u32::try_from(b).ok().and_then(|b| a.checked_pow(b))
+            }
+            Self::BitAnd => Some(a & b),
+            Self::BitOr => Some(a | b),
+            Self::BitXor => Some(a ^ b),
+            Self::ShiftLeft => {
+                let shift = Self::shift_amount(b, line)?;
+                // shifting back must give `a` again, or bits were lost
+                a.checked_shl(shift).filter(|result| result >> shift == a)
             }
-            Self::Modulo => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    if b == 0.0 {
+            Self::ShiftRight => {
+                let shift = Self::shift_amount(b, line)?;
+                Some(a >> shift.min(63))
+            }
+            Self::Greater => return Ok(Value::Bool(a > b)),
+            Self::Less => return Ok(Value::Bool(a < b)),
+            Self::Equal => return Ok(Value::Bool(a == b)),
+        };
+        match result {
+            Some(result) => return Ok(Value::Int(result)),
+            // overflowed, so redo it without a limit
+            None => return self.eval_big(BigInt::from_i64(a), BigInt::from_i64(b), line),
+        }
+    }
+
+    fn eval_big(&self, a: BigInt, b: BigInt, line: usize) -> Result<Value, QalamError> {
+        if b.is_zero() && self.is_division() {
+            return Err(QalamError::with_line_runtime(
+                "Cannot divide by zero!",
+                line,
+            ));
+        }
+        let result = match self {
+            Self::Add => a.add(&b),
+            Self::Subtract => a.sub(&b),
+            Self::Mult => a.mul(&b),
+            Self::Div => return Ok(Value::Number(a.to_f64() / b.to_f64())),
+            Self::IntDiv => a.div_rem(&b).unwrap().0,
+            Self::Modulo => a.div_rem(&b).unwrap().1,
+            Self::Pow => return Self::pow_big(a, b, line),
+            Self::BitAnd => a.bitand(&b),
+            Self::BitOr => a.bitor(&b),
+            Self::BitXor => a.bitxor(&b),
+            Self::ShiftLeft | Self::ShiftRight => {
+                let shift = match b.to_i64() {
+                    Some(shift) => Self::shift_amount(shift, line)?,
+                    None if b > BigInt::from_i64(0) => Self::shift_amount(i64::MAX, line)?,
+                    None => Self::shift_amount(-1, line)?,
+                };
+                match self {
+                    Self::ShiftLeft if a.is_zero() => a,
+                    Self::ShiftLeft if a.bits() + shift as u64 > MAX_RESULT_BITS => {
                         return Err(QalamError::with_line_runtime(
-                            "Cannot divide by zero!",
+                            "Shift amount is too large.",
                             line,
                         ));
                     }
-                    return Ok(Value::Number(a % b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
+                    Self::ShiftLeft => a.shl(shift),
+                    _ => a.shr(shift),
                 }
             }
+            Self::Greater => return Ok(Value::Bool(a > b)),
+            Self::Less => return Ok(Value::Bool(a < b)),
             Self::Equal => return Ok(Value::Bool(a == b)),
-            Self::Greater => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    return Ok(Value::Bool(a > b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
-                }
+        };
+        return Ok(Value::from_big(result));
+    }
+
+    fn pow_big(a: BigInt, b: BigInt, line: usize) -> Result<Value, QalamError> {
+        let one = BigInt::from_i64(1);
+        if b < BigInt::from_i64(0) {
+            return Ok(Value::Number(a.to_f64().powf(b.to_f64())));
+        }
+        // 0, 1 and -1 stay small however large the exponent is
+        if b.is_zero() {
+            return Ok(Value::Int(1));
+        }
+        if a.is_zero() || a == one {
+            return Ok(Value::from_big(a));
+        }
+        if a == one.neg() {
+            let odd = !b.bitand(&one).is_zero();
+            return Ok(Value::Int(if odd { -1 } else { 1 }));
+        }
+        match b.to_i64() {
+            Some(exp) if a.bits().saturating_mul(exp as u64) <= MAX_RESULT_BITS => {
+                return Ok(Value::from_big(a.pow(exp as u32)));
             }
-            Self::Less => {
-                if let (Value::Number(a), Value::Number(b)) = (a, b) {
-                    return Ok(Value::Bool(a < b));
-                } else {
-                    return Err(QalamError::with_line_runtime(
-                        "Operands must be numbers!",
-                        line,
-                    ));
-                }
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Exponent is too large.",
+                    line,
+                ));
             }
         }
     }
+
+    fn eval_float(&self, a: f64, b: f64, line: usize) -> Result<Value, QalamError> {
+        if b == 0.0 && self.is_division() {
+            return Err(QalamError::with_line_runtime(
+                "Cannot divide by zero!",
+                line,
+            ));
+        }
+        let result = match self {
+            Self::Add => a + b,
+            Self::Subtract => a - b,
+            Self::Mult => a * b,
+            Self::Div => a / b,
+            Self::IntDiv => (a / b).trunc(),
+            Self::Modulo => a % b,
+            Self::Pow => a.powf(b),
+            Self::BitAnd | Self::BitOr | Self::BitXor | Self::ShiftLeft | Self::ShiftRight => {
+                return Err(QalamError::with_line_runtime(
+                    "Operands must be integers!",
+                    line,
+                ));
+            }
+            Self::Greater => return Ok(Value::Bool(a > b)),
+            Self::Less => return Ok(Value::Bool(a < b)),
+            Self::Equal => return Ok(Value::Bool(a == b)),
+        };
+        return Ok(Value::Number(result));
+    }
 }
 
 impl Display for BinaryOp {
@@ -116,10 +306,17 @@ impl Display for BinaryOp {
             BinaryOp::Subtract => "-",
             BinaryOp::Mult => "*",
             BinaryOp::Div => "/",
+            BinaryOp::IntDiv => "~/",
             BinaryOp::Equal => "==",
             BinaryOp::Greater => ">",
             BinaryOp::Less => "<",
             BinaryOp::Modulo => "%",
+            BinaryOp::Pow => "**",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::ShiftLeft => "<<",
+            BinaryOp::ShiftRight => ">>",
         };
         write!(f, "{}", op_str)
     }
@@ -146,9 +343,9 @@ impl OperationBase for Binary {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
@@ -156,7 +353,7 @@ impl OperationBase for Binary {
         let a = stack.borrow_mut().pop().unwrap();
         let val = self.op.eval(a, b, line)?;
         stack.borrow_mut().push(val);
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -173,3 +370,130 @@ impl Display for Binary {
         write!(f, "{:<16} '{}'", "OP_BINARY", self.op)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::BinaryOp;
+    use crate::value::Value;
+
+    #[test]
+    fn test_int_promotion() {
+        let sum = BinaryOp::Add.eval(Value::Int(1), Value::Number(0.5), 1);
+        assert!(sum.unwrap() == Value::Number(1.5));
+        let quotient = BinaryOp::IntDiv.eval(Value::Int(7), Value::Int(2), 1);
+        assert!(quotient.unwrap() == Value::Int(3));
+        let product = BinaryOp::Mult.eval(Value::Int(i64::MAX), Value::Int(2), 1);
+        assert_eq!(product.unwrap().to_string(), "18446744073709551614");
+        let back = BinaryOp::Subtract.eval(
+            BinaryOp::Add
+                .eval(Value::Int(i64::MAX), Value::Int(1), 1)
+                .unwrap(),
+            Value::Int(1),
+            1,
+        );
+        assert!(back.unwrap() == Value::Int(i64::MAX));
+        let power = BinaryOp::Pow.eval(Value::Int(2), Value::Int(64), 1);
+        assert_eq!(power.unwrap().to_string(), "18446744073709551616");
+        let power = BinaryOp::Pow.eval(Value::Int(2), Value::Int(-2), 1);
+        assert!(power.unwrap() == Value::Number(0.25));
+        let shifted = BinaryOp::ShiftLeft.eval(Value::Int(1), Value::Int(64), 1);
+        assert_eq!(shifted.unwrap().to_string(), "18446744073709551616");
+        let err = BinaryOp::BitAnd.eval(Value::Number(1.5), Value::Int(1), 2);
+        assert_eq!(
+            format!("{}", err.err().unwrap()),
+            "RuntimeError: Operands must be integers!\n\tat line 2"
+        );
+    }
+
+    #[test]
+    fn test_pow_limits() {
+        let huge = BinaryOp::Pow
+            .eval(Value::Int(2), Value::Int(40), 1)
+            .unwrap();
+        let power = |a: i64, b: &Value| BinaryOp::Pow.eval(Value::Int(a), b.clone(), 1);
+        assert!(power(1, &huge).unwrap() == Value::Int(1));
+        assert!(power(-1, &huge).unwrap() == Value::Int(1));
+        assert!(power(0, &huge).unwrap() == Value::Int(0));
+        let odd = BinaryOp::Add.eval(huge.clone(), Value::Int(1), 1).unwrap();
+        assert!(power(-1, &odd).unwrap() == Value::Int(-1));
+        let err = power(2, &Value::Int(4000000000)).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "RuntimeError: Exponent is too large.\n\tat line 1"
+        );
+    }
+
+    #[test]
+    fn test_shift_limits() {
+        let huge = BinaryOp::Pow
+            .eval(Value::Int(2), Value::Int(70), 1)
+            .unwrap();
+        let shift = |op: BinaryOp, a: i64, b: &Value| op.eval(Value::Int(a), b.clone(), 1);
+        assert!(shift(BinaryOp::ShiftRight, 5, &huge).unwrap() == Value::Int(0));
+        assert!(shift(BinaryOp::ShiftRight, -5, &huge).unwrap() == Value::Int(-1));
+        assert!(shift(BinaryOp::ShiftLeft, 0, &huge).unwrap() == Value::Int(0));
+        let err = shift(BinaryOp::ShiftLeft, 3, &Value::Int(4000000000)).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "RuntimeError: Shift amount is too large.\n\tat line 1"
+        );
+    }
+
+    #[test]
+    fn test_mixed_comparison() {
+        let compare =
+            |op: BinaryOp, a: Value, b: Value| op.eval(a, b, 1).unwrap() == Value::Bool(true);
+        let big = BinaryOp::Add
+            .eval(
+                BinaryOp::Pow
+                    .eval(Value::Int(2), Value::Int(64), 1)
+                    .unwrap(),
+                Value::Int(1),
+                1,
+            )
+            .unwrap();
+        assert!(compare(
+            BinaryOp::Greater,
+            big.clone(),
+            Value::Number(18446744073709551616.0)
+        ));
+        assert!(compare(
+            BinaryOp::Less,
+            Value::Number(18446744073709551616.0),
+            big
+        ));
+        let int = Value::Int(9007199254740993);
+        assert!(compare(
+            BinaryOp::Greater,
+            int.clone(),
+            Value::Number(9007199254740992.0)
+        ));
+        assert!(!compare(
+            BinaryOp::Less,
+            int,
+            Value::Number(9007199254740992.0)
+        ));
+        assert!(compare(BinaryOp::Less, Value::Int(2), Value::Number(2.5)));
+        assert!(compare(
+            BinaryOp::Greater,
+            Value::Int(3),
+            Value::Number(2.5)
+        ));
+        assert!(compare(
+            BinaryOp::Greater,
+            Value::Int(-2),
+            Value::Number(-2.5)
+        ));
+        assert!(!compare(BinaryOp::Less, Value::Int(2), Value::Number(2.0)));
+        assert!(compare(
+            BinaryOp::Less,
+            Value::Int(i64::MAX),
+            Value::Number(f64::INFINITY)
+        ));
+        assert!(!compare(
+            BinaryOp::Less,
+            Value::Int(1),
+            Value::Number(f64::NAN)
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+use super::operation::{OpCode, OperationBase};
+use crate::value::class::Instance;
+use crate::value::closure::Closure;
+use crate::vm::call_frame::{CallFrame, FRAMES_MAX};
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct Call {
+    code: OpCode,
+    arg_count: usize,
+}
+
+impl Call {
+    pub fn new(arg_count: usize) -> Self {
+        return Self {
+            code: OpCode::Call,
+            arg_count,
+        };
+    }
+
+    fn call_closure(
+        closure: Rc<Closure>,
+        arg_count: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        line: usize,
+    ) -> Result<(), QalamError> {
+        if arg_count != closure.function.arity {
+            return Err(QalamError::with_line_runtime(
+                &format!(
+                    "Expected {} arguments but got {} calling {}.",
+                    closure.function.arity, arg_count, closure.function
+                ),
+                line,
+            ));
+        }
+        if call_frame.borrow().len() >= FRAMES_MAX {
+            return Err(QalamError::with_line_runtime("Stack overflow.", line));
+        }
+        // the callee sits just below its arguments and occupies slot 0 of the new frame
+        let base = stack.borrow().len() - arg_count - 1;
+        call_frame.borrow_mut().push(CallFrame::new(closure, base));
+        return Ok(());
+    }
+
+    pub fn call_value(
+        callee: Value,
+        arg_count: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        line: usize,
+    ) -> Result<(), QalamError> {
+        let callee_slot = stack.borrow().len() - arg_count - 1;
+        match callee {
+            Value::Closure(closure) => {
+                return Self::call_closure(closure, arg_count, stack, call_frame, line);
+            }
+            Value::Class(class) => {
+                // the new instance takes the place of the class so it becomes 'nafs' for the initializer
+                let instance = Instance::new(class.clone());
+                stack.borrow_mut()[callee_slot] = Value::Instance(Rc::new(RefCell::new(instance)));
+                let init = class.borrow().methods.get(&String::from("init"));
+                if let Some(Value::Closure(init)) = init {
+                    return Self::call_closure(init, arg_count, stack, call_frame, line);
+                } else if arg_count != 0 {
+                    return Err(QalamError::with_line_runtime(
+                        &format!("Expected 0 arguments but got {}.", arg_count),
+                        line,
+                    ));
+                }
+                return Ok(());
+            }
+            Value::Native(native) => {
+                if arg_count != native.arity {
+                    return Err(QalamError::with_line_runtime(
+                        &format!("Expected {} arguments but got {}.", native.arity, arg_count),
+                        line,
+                    ));
+                }
+                let args = stack.borrow_mut().split_off(callee_slot + 1);
+                let result = (native.function)(&args).map_err(|e| e.at_line(line))?;
+                // replace the callee with the result, as a return would
+                stack.borrow_mut().truncate(callee_slot);
+                stack.borrow_mut().push(result);
+                return Ok(());
+            }
+            Value::BoundMethod(bound) => {
+                stack.borrow_mut()[callee_slot] = bound.receiver.clone();
+                return Self::call_closure(
+                    bound.method.clone(),
+                    arg_count,
+                    stack,
+                    call_frame,
+                    line,
+                );
+            }
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Can only call functions and classes.",
+                    line,
+                ));
+            }
+        }
+    }
+}
+
+impl OperationBase for Call {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let callee = {
+            let stack = stack.borrow();
+            stack[stack.len() - 1 - self.arg_count].clone()
+        };
+        Self::call_value(callee, self.arg_count, stack, call_frame, line)?;
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for Call {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_CALL", self.arg_count)
+    }
+}
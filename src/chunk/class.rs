@@ -0,0 +1,232 @@
+use super::operation::{OpCode, OperationBase};
+use crate::value::class::{BoundMethod, Class};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct ClassOp {
+    code: OpCode,
+    name: String,
+}
+
+impl ClassOp {
+    pub fn new(name: String) -> Self {
+        return Self {
+            code: OpCode::Class,
+            name,
+        };
+    }
+}
+
+impl OperationBase for ClassOp {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        _: usize,
+    ) -> Result<usize, QalamError> {
+        let class = Class::new(self.name.clone());
+        stack
+            .borrow_mut()
+            .push(Value::Class(Rc::new(RefCell::new(class))));
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for ClassOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_CLASS", self.name)
+    }
+}
+
+pub struct Method {
+    code: OpCode,
+    name: String,
+}
+
+impl Method {
+    pub fn new(name: String) -> Self {
+        return Self {
+            code: OpCode::Method,
+            name,
+        };
+    }
+}
+
+impl OperationBase for Method {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let method = stack.borrow_mut().pop().unwrap();
+        let top = stack.borrow().len() - 1;
+        if let Value::Class(class) = &stack.borrow()[top] {
+            class.borrow_mut().methods.add(self.name.clone(), method);
+        } else {
+            return Err(QalamError::with_line_runtime(
+                "Methods can only be defined on classes.",
+                line,
+            ));
+        }
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_METHOD", self.name)
+    }
+}
+
+pub struct Inherit {
+    code: OpCode,
+}
+
+impl Inherit {
+    pub fn new() -> Self {
+        return Self {
+            code: OpCode::Inherit,
+        };
+    }
+}
+
+impl OperationBase for Inherit {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let subclass = stack.borrow_mut().pop().unwrap();
+        let top = stack.borrow().len() - 1;
+        let superclass = stack.borrow()[top].clone();
+        match (superclass, subclass) {
+            (Value::Class(superclass), Value::Class(subclass)) => {
+                // copied down before the subclass' own methods are added, so those override
+                subclass
+                    .borrow_mut()
+                    .methods
+                    .add_all(&superclass.borrow().methods);
+            }
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Superclass must be a class.",
+                    line,
+                ));
+            }
+        }
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for Inherit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16}", "OP_INHERIT")
+    }
+}
+
+pub struct GetSuper {
+    code: OpCode,
+    name: String,
+}
+
+impl GetSuper {
+    pub fn new(name: String) -> Self {
+        return Self {
+            code: OpCode::GetSuper,
+            name,
+        };
+    }
+}
+
+impl OperationBase for GetSuper {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let superclass = stack.borrow_mut().pop().unwrap();
+        let receiver = stack.borrow_mut().pop().unwrap();
+        let method = match &superclass {
+            Value::Class(superclass) => superclass.borrow().methods.get(&self.name),
+            _ => None,
+        };
+        if let Some(Value::Closure(method)) = method {
+            let bound = BoundMethod::new(receiver, method);
+            stack.borrow_mut().push(Value::BoundMethod(Rc::new(bound)));
+            return Ok(curr_offset + 1);
+        }
+        return Err(QalamError::with_line_runtime(
+            &format!("Undefined property '{}'.", self.name),
+            line,
+        ));
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for GetSuper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_GET_SUPER", self.name)
+    }
+}
@@ -0,0 +1,127 @@
+use super::operation::{OpCode, OperationBase};
+use crate::compiler::Upvalue;
+use crate::value::closure::Closure;
+use crate::value::function::Function;
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct ClosureOp {
+    code: OpCode,
+    function: Rc<Function>,
+    upvalues: Vec<Upvalue>,
+}
+
+impl ClosureOp {
+    pub fn new(function: Rc<Function>, upvalues: Vec<Upvalue>) -> Self {
+        return Self {
+            code: OpCode::Closure,
+            function,
+            upvalues,
+        };
+    }
+}
+
+impl OperationBase for ClosureOp {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        globals: Rc<RefCell<Table>>,
+        _: usize,
+    ) -> Result<usize, QalamError> {
+        let mut upvalues = Vec::new();
+        {
+            let mut frames = call_frame.borrow_mut();
+            let frame = frames.last_mut().unwrap();
+            for upvalue in self.upvalues.iter() {
+                if upvalue.is_local {
+                    let slot = frame.base + upvalue.index;
+                    upvalues.push(frame.capture_upvalue(slot));
+                } else {
+                    upvalues.push(frame.closure.upvalues[upvalue.index].clone());
+                }
+            }
+        }
+        let closure = Closure::new(self.function.clone(), upvalues, globals);
+        stack.borrow_mut().push(Value::Closure(Rc::new(closure)));
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for ClosureOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut str = format!("{:<16} '{}'", "OP_CLOSURE", self.function);
+        for upvalue in self.upvalues.iter() {
+            let kind = if upvalue.is_local { "local" } else { "upvalue" };
+            str += &format!(" [{} {}]", kind, upvalue.index);
+        }
+        write!(f, "{}", str)
+    }
+}
+
+pub struct CloseUpvalue {
+    code: OpCode,
+}
+
+impl CloseUpvalue {
+    pub fn new() -> Self {
+        return Self {
+            code: OpCode::CloseUpvalue,
+        };
+    }
+}
+
+impl OperationBase for CloseUpvalue {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        _: usize,
+    ) -> Result<usize, QalamError> {
+        let top = stack.borrow().len() - 1;
+        call_frame
+            .borrow_mut()
+            .last_mut()
+            .unwrap()
+            .close_upvalues(top, &stack.borrow());
+        stack.borrow_mut().pop();
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for CloseUpvalue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16}", "OP_CLOSE_UPVALUE")
+    }
+}
@@ -1,4 +1,5 @@
 use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
@@ -26,15 +27,15 @@ impl OperationBase for Constant {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
         stack.borrow_mut().push(self.operand.clone());
 
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
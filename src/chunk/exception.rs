@@ -0,0 +1,162 @@
+use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::{CallFrame, Handler};
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Enters a 'hawil' block, installing a handler that resumes `jump` operations ahead when an error is raised.
+pub struct Try {
+    code: OpCode,
+    pub jump: Option<usize>,
+}
+
+impl Try {
+    pub fn new() -> Self {
+        Self {
+            code: OpCode::Try,
+            jump: None,
+        }
+    }
+}
+
+impl OperationBase for Try {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let jump = match self.jump {
+            Some(jump) => jump,
+            None => return Err(QalamError::with_line_compile("Jump was not patched!", line)),
+        };
+        let handler = Handler {
+            ip: curr_offset + jump + 1,
+            stack_len: stack.borrow().len(),
+        };
+        call_frame
+            .borrow_mut()
+            .last_mut()
+            .unwrap()
+            .handlers
+            .push(handler);
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for Try {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{:?}'", "OP_TRY", self.jump)
+    }
+}
+
+/// Leaves a 'hawil' block, removing its handler.
+pub struct EndTry {
+    code: OpCode,
+}
+
+impl EndTry {
+    pub fn new() -> Self {
+        Self {
+            code: OpCode::EndTry,
+        }
+    }
+}
+
+impl OperationBase for EndTry {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        _: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        _: usize,
+    ) -> Result<usize, QalamError> {
+        call_frame.borrow_mut().last_mut().unwrap().handlers.pop();
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for EndTry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OP_END_TRY")
+    }
+}
+
+/// Raises the value on top of the stack as an error.
+pub struct Throw {
+    code: OpCode,
+}
+
+impl Throw {
+    pub fn new() -> Self {
+        Self {
+            code: OpCode::Throw,
+        }
+    }
+}
+
+impl OperationBase for Throw {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn eval(
+        &self,
+        _: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let value = stack.borrow_mut().pop().unwrap();
+        return Err(QalamError::thrown(value, line));
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for Throw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OP_THROW")
+    }
+}
@@ -0,0 +1,57 @@
+use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Pushes the module loaded from `path`. Loading runs the module's code, so
+/// the VM executes this operation itself rather than through `eval`.
+pub struct Import {
+    code: OpCode,
+    pub path: String,
+}
+
+impl Import {
+    pub fn new(path: String) -> Self {
+        return Self {
+            code: OpCode::Import,
+            path,
+        };
+    }
+}
+
+impl OperationBase for Import {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        _: usize,
+        _: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        return Err(QalamError::with_line_runtime(
+            "Modules can only be imported by the VM.",
+            line,
+        ));
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for Import {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_IMPORT", self.path)
+    }
+}
@@ -0,0 +1,198 @@
+use super::operation::{OpCode, OperationBase};
+use crate::value::float_to_int;
+use crate::value::map::MapKey;
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+fn list_index(index: &Value, len: usize, line: usize) -> Result<usize, QalamError> {
+    let position = match index {
+        Value::Int(index) => usize::try_from(*index).ok(),
+        Value::BigInt(index) => index.to_i64().and_then(|index| usize::try_from(index).ok()),
+        Value::Number(index) if index.fract() == 0.0 => {
+            float_to_int(*index).and_then(|index| usize::try_from(index).ok())
+        }
+        _ => {
+            return Err(QalamError::with_line_runtime(
+                "List index must be an integer.",
+                line,
+            ))
+        }
+    };
+    match position {
+        Some(position) if position < len => return Ok(position),
+        _ => {
+            return Err(QalamError::with_line_runtime(
+                &format!(
+                    "List index {} out of bounds for length {}.",
+                    index.repr(),
+                    len
+                ),
+                line,
+            ))
+        }
+    }
+}
+
+pub struct GetIndex {
+    code: OpCode,
+    /// Leave the target and index on the stack, for compound assignment.
+    keep: bool,
+    /// Read a missing map key as `ghaib` instead of failing, for `??=`.
+    optional: bool,
+}
+
+impl GetIndex {
+    pub fn new(keep: bool) -> Self {
+        return Self {
+            code: OpCode::GetIndex,
+            keep,
+            optional: false,
+        };
+    }
+
+    pub fn optional() -> Self {
+        return Self {
+            code: OpCode::GetIndex,
+            keep: true,
+            optional: true,
+        };
+    }
+}
+
+impl OperationBase for GetIndex {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let top = stack.borrow().len() - 1;
+        let index = stack.borrow()[top].clone();
+        let target = stack.borrow()[top - 1].clone();
+        let val = match target {
+            Value::List(list) => {
+                let list = list.borrow();
+                list[list_index(&index, list.len(), line)?].clone()
+            }
+            Value::Map(map) => match map
+                .borrow()
+                .get(&MapKey::from_value(&index).map_err(|e| e.at_line(line))?)
+            {
+                Some(val) => val,
+                None if self.optional => Value::Null,
+                None => {
+                    return Err(QalamError::with_line_runtime(
+                        &format!("Key {} not found in map.", index.repr()),
+                        line,
+                    ))
+                }
+            },
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Can only index lists and maps.",
+                    line,
+                ))
+            }
+        };
+        if !self.keep {
+            stack.borrow_mut().truncate(top - 1);
+        }
+        stack.borrow_mut().push(val);
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for GetIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.optional {
+            return write!(f, "{:<16} 'optional'", "OP_GET_INDEX");
+        }
+        if self.keep {
+            return write!(f, "{:<16} 'keep'", "OP_GET_INDEX");
+        }
+        write!(f, "{:<16}", "OP_GET_INDEX")
+    }
+}
+
+pub struct SetIndex {
+    code: OpCode,
+}
+
+impl SetIndex {
+    pub fn new() -> Self {
+        return Self {
+            code: OpCode::SetIndex,
+        };
+    }
+}
+
+impl OperationBase for SetIndex {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let val = stack.borrow_mut().pop().unwrap();
+        let index = stack.borrow_mut().pop().unwrap();
+        let target = stack.borrow_mut().pop().unwrap();
+        match target {
+            Value::List(list) => {
+                let mut list = list.borrow_mut();
+                let index = list_index(&index, list.len(), line)?;
+                list[index] = val.clone();
+            }
+            Value::Map(map) => {
+                let key = MapKey::from_value(&index).map_err(|e| e.at_line(line))?;
+                map.borrow_mut().insert(key, val.clone());
+            }
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Can only index lists and maps.",
+                    line,
+                ))
+            }
+        }
+        // the assignment evaluates to the assigned value
+        stack.borrow_mut().push(val);
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for SetIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16}", "OP_SET_INDEX")
+    }
+}
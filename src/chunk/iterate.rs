@@ -0,0 +1,164 @@
+use super::binary::BinaryOp;
+use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Advances a 'tawaf ... fi' loop over the iterable in local `slot`, whose
+/// cursor is in the slot after it. Pushes the next item, or jumps `jump`
+/// operations ahead once there are none left.
+pub struct IterNext {
+    code: OpCode,
+    slot: usize,
+    pub jump: Option<usize>,
+}
+
+impl IterNext {
+    pub fn new(slot: usize) -> Self {
+        Self {
+            code: OpCode::IterNext,
+            slot,
+            jump: None,
+        }
+    }
+}
+
+impl OperationBase for IterNext {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let jump = match self.jump {
+            Some(jump) => jump,
+            None => return Err(QalamError::with_line_compile("Jump was not patched!", line)),
+        };
+        let slot = call_frame.borrow().last().unwrap().base + self.slot;
+        let iterable = stack.borrow()[slot].clone();
+        let cursor = match stack.borrow()[slot + 1] {
+            Value::Int(cursor) => cursor as usize,
+            _ => 0,
+        };
+        match iterable.next_item(cursor).map_err(|e| e.at_line(line))? {
+            Some((item, next)) => {
+                let mut stack = stack.borrow_mut();
+                stack[slot + 1] = Value::Int(next as i64);
+                stack.push(item);
+                return Ok(curr_offset + 1);
+            }
+            None => return Ok(curr_offset + jump + 1),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for IterNext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} {} '{:?}'", "OP_ITER_NEXT", self.slot, self.jump)
+    }
+}
+
+/// Advances a 'tawaf ... fi a..b' loop without building a list: the counter
+/// is in local `slot` and the bound in the slot after it. Pushes the counter
+/// and increments it, or jumps `jump` operations ahead once it passes the bound.
+pub struct RangeNext {
+    code: OpCode,
+    slot: usize,
+    inclusive: bool,
+    pub jump: Option<usize>,
+}
+
+impl RangeNext {
+    pub fn new(slot: usize, inclusive: bool) -> Self {
+        Self {
+            code: OpCode::RangeNext,
+            slot,
+            inclusive,
+            jump: None,
+        }
+    }
+}
+
+impl OperationBase for RangeNext {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let jump = match self.jump {
+            Some(jump) => jump,
+            None => return Err(QalamError::with_line_compile("Jump was not patched!", line)),
+        };
+        let slot = call_frame.borrow().last().unwrap().base + self.slot;
+        let counter = stack.borrow()[slot].clone();
+        let bound = stack.borrow()[slot + 1].clone();
+        let done = if self.inclusive {
+            BinaryOp::Greater.eval(counter.clone(), bound, line)
+        } else {
+            BinaryOp::Less
+                .eval(counter.clone(), bound, line)
+                .map(|less| Value::Bool(less.is_falsy()))
+        }
+        .map_err(|_| QalamError::with_line_runtime("Range bounds must be numbers.", line))?;
+        if !done.is_falsy() {
+            return Ok(curr_offset + jump + 1);
+        }
+        let next = BinaryOp::Add.eval(counter.clone(), Value::Int(1), line)?;
+        let mut stack = stack.borrow_mut();
+        stack[slot] = next;
+        stack.push(counter);
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for RangeNext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let range = if self.inclusive { "..=" } else { ".." };
+        write!(
+            f,
+            "{:<16} {} '{}' '{:?}'",
+            "OP_RANGE_NEXT", self.slot, range, self.jump
+        )
+    }
+}
@@ -1,4 +1,5 @@
 use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::any::Any;
@@ -25,11 +26,15 @@ impl OperationBase for Jump {
         return self.code.clone();
     }
 
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
     fn eval(
         &self,
         curr_offset: usize,
         _: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
@@ -76,11 +81,15 @@ impl OperationBase for FalseJump {
         return self.code.clone();
     }
 
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
     fn eval(
         &self,
         curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
@@ -90,7 +99,7 @@ impl OperationBase for FalseJump {
             if cond.is_falsy() {
                 return Ok(curr_offset + jump + 1);
             } else {
-                return Ok(0);
+                return Ok(curr_offset + 1);
             }
         } else {
             return Err(QalamError::with_line_compile("Jump was not patched!", line));
@@ -114,6 +123,64 @@ impl Display for FalseJump {
     }
 }
 
+/// Jumps when the top of the stack is `ghaib`, leaving it in place. Unlike
+/// `FalseJump` it doesn't treat `batil` as missing.
+pub struct NullJump {
+    code: OpCode,
+    pub jump: Option<usize>,
+}
+
+impl NullJump {
+    pub fn new() -> Self {
+        Self {
+            code: OpCode::NullJump,
+            jump: None,
+        }
+    }
+}
+
+impl OperationBase for NullJump {
+    fn disassemble(&self) -> OpCode {
+        return self.code.clone();
+    }
+
+    fn patch_jump(&mut self, offset: usize) {
+        self.jump = Some(offset);
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        if let Some(jump) = self.jump {
+            if let Some(Value::Null) = stack.borrow().last() {
+                return Ok(curr_offset + jump + 1);
+            }
+            return Ok(curr_offset + 1);
+        } else {
+            return Err(QalamError::with_line_compile("Jump was not patched!", line));
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl Display for NullJump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{:?}'", "OP_NULL_JUMP", self.jump)
+    }
+}
+
 pub struct LoopJump {
     code: OpCode,
     pub jump: usize,
@@ -137,7 +204,7 @@ impl OperationBase for LoopJump {
         &self,
         curr_offset: usize,
         _: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
@@ -0,0 +1,57 @@
+use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct BuildList {
+    code: OpCode,
+    count: usize,
+}
+
+impl BuildList {
+    pub fn new(count: usize) -> Self {
+        return Self {
+            code: OpCode::BuildList,
+            count,
+        };
+    }
+}
+
+impl OperationBase for BuildList {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        _: usize,
+    ) -> Result<usize, QalamError> {
+        let start = stack.borrow().len() - self.count;
+        let items = stack.borrow_mut().split_off(start);
+        stack
+            .borrow_mut()
+            .push(Value::List(Rc::new(RefCell::new(items))));
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for BuildList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_BUILD_LIST", self.count)
+    }
+}
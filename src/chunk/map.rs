@@ -0,0 +1,66 @@
+use super::operation::{OpCode, OperationBase};
+use crate::value::map::{Map, MapKey};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct BuildMap {
+    code: OpCode,
+    count: usize,
+}
+
+impl BuildMap {
+    pub fn new(count: usize) -> Self {
+        return Self {
+            code: OpCode::BuildMap,
+            count,
+        };
+    }
+}
+
+impl OperationBase for BuildMap {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        // keys and values alternate on the stack
+        let start = stack.borrow().len() - self.count * 2;
+        let items = stack.borrow_mut().split_off(start);
+        let mut map = Map::new();
+        for pair in items.chunks(2) {
+            map.insert(
+                MapKey::from_value(&pair[0]).map_err(|e| e.at_line(line))?,
+                pair[1].clone(),
+            );
+        }
+        stack
+            .borrow_mut()
+            .push(Value::Map(Rc::new(RefCell::new(map))));
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for BuildMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_BUILD_MAP", self.count)
+    }
+}
@@ -1,9 +1,20 @@
 pub mod binary;
+pub mod call;
+pub mod class;
+pub mod closure;
 pub mod constant;
+pub mod exception;
+pub mod import;
+pub mod index;
+pub mod iterate;
 pub mod jump;
+pub mod list;
+pub mod map;
 pub mod operation;
+pub mod pattern;
 pub mod pop;
 pub mod print;
+pub mod property;
 pub mod return_op;
 pub mod unary;
 pub mod variable;
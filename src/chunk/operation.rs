@@ -1,4 +1,5 @@
 use crate::value::Value;
+use crate::vm::call_frame::CallFrame;
 use crate::{error::QalamError, vm::table::Table};
 use std::any::Any;
 use std::{cell::RefCell, fmt::Display, rc::Rc};
@@ -17,19 +18,43 @@ pub enum OpCode {
     Set,
     Jump,
     FalseJump,
+    NullJump,
+    MatchRange,
+    Try,
+    EndTry,
+    Throw,
+    Import,
+    IterNext,
+    RangeNext,
     LoopJump,
+    Call,
+    Closure,
+    CloseUpvalue,
+    Class,
+    Method,
+    Inherit,
+    GetSuper,
+    BuildList,
+    BuildMap,
+    GetIndex,
+    SetIndex,
+    GetProperty,
+    SetProperty,
 }
 
 pub trait OperationBase {
     fn disassemble(&self) -> OpCode;
+    /// Executes the operation and returns the offset of the next instruction to run in the current frame.
     fn eval(
         &self,
         curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        call_frame: Rc<RefCell<Vec<String>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
         globals: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError>;
+    /// Sets the offset of a forward jump emitted before its target was known. Ops that don't jump ignore it.
+    fn patch_jump(&mut self, _offset: usize) {}
     fn as_any(&self) -> &dyn Any;
     fn as_any_mut(&mut self) -> &mut dyn Any;
 }
@@ -0,0 +1,76 @@
+use super::binary::BinaryOp;
+use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// Tests a `tabiq` subject against a range pattern. Values that can't be
+/// compared with the bounds, like strings, simply don't match.
+pub struct MatchRange {
+    code: OpCode,
+    inclusive: bool,
+}
+
+impl MatchRange {
+    pub fn new(inclusive: bool) -> Self {
+        return Self {
+            code: OpCode::MatchRange,
+            inclusive,
+        };
+    }
+
+    fn contains(&self, value: Value, lo: Value, hi: Value, line: usize) -> bool {
+        let below = BinaryOp::Less.eval(value.clone(), lo, line);
+        let above = if self.inclusive {
+            BinaryOp::Greater.eval(value, hi, line)
+        } else {
+            BinaryOp::Less
+                .eval(value, hi, line)
+                .map(|less| Value::Bool(less.is_falsy()))
+        };
+        return matches!(
+            (below, above),
+            (Ok(Value::Bool(false)), Ok(Value::Bool(false)))
+        );
+    }
+}
+
+impl OperationBase for MatchRange {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let hi = stack.borrow_mut().pop().unwrap();
+        let lo = stack.borrow_mut().pop().unwrap();
+        let value = stack.borrow_mut().pop().unwrap();
+        let matched = self.contains(value, lo, hi, line);
+        stack.borrow_mut().push(Value::Bool(matched));
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for MatchRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let range = if self.inclusive { "..=" } else { ".." };
+        write!(f, "{:<16} '{}'", "OP_MATCH_RANGE", range)
+    }
+}
@@ -1,4 +1,5 @@
 use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
@@ -22,14 +23,14 @@ impl OperationBase for Pop {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
         stack.borrow_mut().pop().unwrap();
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -68,16 +69,16 @@ impl OperationBase for PopN {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
         for _ in 0..self.n {
             stack.borrow_mut().pop().unwrap();
         }
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -1,4 +1,5 @@
 use super::operation::{OpCode, OperationBase};
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
@@ -24,9 +25,9 @@ impl OperationBase for Print {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
@@ -34,7 +35,7 @@ impl OperationBase for Print {
         println!("{}", popped);
         // stack.borrow_mut().push(self.operand.clone());
 
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
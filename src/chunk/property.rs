@@ -0,0 +1,163 @@
+use super::operation::{OpCode, OperationBase};
+use crate::value::class::BoundMethod;
+use crate::vm::call_frame::CallFrame;
+use crate::vm::table::Table;
+use crate::{error::QalamError, value::Value};
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub struct GetProperty {
+    code: OpCode,
+    name: String,
+}
+
+impl GetProperty {
+    pub fn new(name: String) -> Self {
+        return Self {
+            code: OpCode::GetProperty,
+            name,
+        };
+    }
+}
+
+impl OperationBase for GetProperty {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let target = stack.borrow_mut().pop().unwrap();
+        let instance = match target {
+            Value::Instance(instance) => instance,
+            Value::Error(error) => match error.get(&self.name) {
+                Some(val) => {
+                    stack.borrow_mut().push(val);
+                    return Ok(curr_offset + 1);
+                }
+                None => {
+                    return Err(QalamError::with_line_runtime(
+                        &format!("Undefined property '{}'.", self.name),
+                        line,
+                    ))
+                }
+            },
+            Value::Module(module) => match module.export(&self.name) {
+                Some(val) => {
+                    stack.borrow_mut().push(val);
+                    return Ok(curr_offset + 1);
+                }
+                None => {
+                    return Err(QalamError::with_line_runtime(
+                        &format!("Module '{}' has no export '{}'.", module.name, self.name),
+                        line,
+                    ))
+                }
+            },
+            _ => {
+                return Err(QalamError::with_line_runtime(
+                    "Only instances have properties.",
+                    line,
+                ))
+            }
+        };
+
+        // fields shadow methods
+        let field = instance.borrow().fields.get(&self.name);
+        if let Some(field) = field {
+            stack.borrow_mut().push(field);
+            return Ok(curr_offset + 1);
+        }
+        let method = instance.borrow().class.borrow().methods.get(&self.name);
+        if let Some(Value::Closure(method)) = method {
+            let bound = BoundMethod::new(Value::Instance(instance), method);
+            stack.borrow_mut().push(Value::BoundMethod(Rc::new(bound)));
+            return Ok(curr_offset + 1);
+        }
+        return Err(QalamError::with_line_runtime(
+            &format!("Undefined property '{}'.", self.name),
+            line,
+        ));
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for GetProperty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_GET_PROPERTY", self.name)
+    }
+}
+
+pub struct SetProperty {
+    code: OpCode,
+    name: String,
+}
+
+impl SetProperty {
+    pub fn new(name: String) -> Self {
+        return Self {
+            code: OpCode::SetProperty,
+            name,
+        };
+    }
+}
+
+impl OperationBase for SetProperty {
+    fn disassemble(&self) -> OpCode {
+        self.code.clone()
+    }
+
+    fn eval(
+        &self,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
+        _: Rc<RefCell<Table>>,
+        line: usize,
+    ) -> Result<usize, QalamError> {
+        let val = stack.borrow_mut().pop().unwrap();
+        let target = stack.borrow_mut().pop().unwrap();
+        if let Value::Instance(instance) = target {
+            instance
+                .borrow_mut()
+                .fields
+                .add(self.name.clone(), val.clone());
+        } else {
+            return Err(QalamError::with_line_runtime(
+                "Only instances have fields.",
+                line,
+            ));
+        }
+        // the assignment evaluates to the assigned value
+        stack.borrow_mut().push(val);
+        return Ok(curr_offset + 1);
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Display for SetProperty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:<16} '{}'", "OP_SET_PROPERTY", self.name)
+    }
+}
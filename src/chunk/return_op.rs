@@ -1,5 +1,6 @@
 use crate::error::QalamError;
 use crate::value::Value;
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use std::cell::RefCell;
 use std::fmt::Display;
@@ -26,14 +27,19 @@ impl OperationBase for ReturnOp {
 
     fn eval(
         &self,
-        _: usize,
-        _: Rc<RefCell<Vec<Value>>>,
-        call_frame: Rc<RefCell<Vec<String>>>,
+        curr_offset: usize,
+        stack: Rc<RefCell<Vec<Value>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
-        call_frame.borrow_mut().pop();
-        return Ok(0);
+        let result = stack.borrow_mut().pop().unwrap();
+        let mut frame = call_frame.borrow_mut().pop().unwrap();
+        frame.close_upvalues(frame.base, &stack.borrow());
+        // discard the callee and its locals, leaving the result for the caller
+        stack.borrow_mut().truncate(frame.base);
+        stack.borrow_mut().push(result);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -1,4 +1,6 @@
 use super::operation::{OpCode, OperationBase};
+use crate::value::bigint::BigInt;
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
@@ -9,6 +11,9 @@ use std::rc::Rc;
 pub enum UnaryOp {
     Negate,
     Bang,
+    BitNot,
+    /// Converts the operand to its display string, for interpolation.
+    Stringify,
 }
 
 impl Display for UnaryOp {
@@ -16,6 +21,8 @@ impl Display for UnaryOp {
         let op_str = match self {
             UnaryOp::Negate => "-",
             UnaryOp::Bang => "!",
+            UnaryOp::BitNot => "~",
+            UnaryOp::Stringify => "str",
         };
         write!(f, "{}", op_str)
     }
@@ -42,27 +49,44 @@ impl OperationBase for Unary {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         _: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
         let val = stack.borrow_mut().pop().unwrap();
         match self.op {
-            UnaryOp::Negate => {
-                if let Value::Number(val) = val {
-                    stack.borrow_mut().push(Value::Number(-val));
-                } else {
+            UnaryOp::Negate => match val {
+                Value::Number(val) => stack.borrow_mut().push(Value::Number(-val)),
+                Value::Int(val) => match val.checked_neg() {
+                    Some(val) => stack.borrow_mut().push(Value::Int(val)),
+                    None => stack
+                        .borrow_mut()
+                        .push(Value::from_big(BigInt::from_i64(val).neg())),
+                },
+                Value::BigInt(val) => stack.borrow_mut().push(Value::from_big(val.neg())),
+                _ => {
                     return Err(QalamError::new_runtime(&format!(
                         "Operands must be numbers!\n\tat line {}",
                         line
                     )));
                 }
-            }
+            },
             UnaryOp::Bang => stack.borrow_mut().push(Value::Bool(val.is_falsy())),
+            UnaryOp::BitNot => match val {
+                Value::Int(val) => stack.borrow_mut().push(Value::Int(!val)),
+                Value::BigInt(val) => stack.borrow_mut().push(Value::from_big(val.not())),
+                _ => {
+                    return Err(QalamError::with_line_runtime(
+                        "Operand must be an integer!",
+                        line,
+                    ));
+                }
+            },
+            UnaryOp::Stringify => stack.borrow_mut().push(Value::String(val.to_string())),
         }
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -1,4 +1,6 @@
 use super::operation::{OpCode, OperationBase};
+use crate::value::closure::Upvalue;
+use crate::vm::call_frame::CallFrame;
 use crate::vm::table::Table;
 use crate::{error::QalamError, value::Value};
 use std::cell::RefCell;
@@ -26,9 +28,9 @@ impl OperationBase for Define {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        _: Rc<RefCell<Vec<CallFrame>>>,
         globals: Rc<RefCell<Table>>,
         _: usize,
     ) -> Result<usize, QalamError> {
@@ -44,7 +46,7 @@ impl OperationBase for Define {
             .add(name, stack.borrow()[curr_idx()].clone());
         stack.borrow_mut().pop();
 
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -66,6 +68,7 @@ impl Display for Define {
 pub enum Scope {
     Global,
     Local(usize),
+    Upvalue(usize),
 }
 
 pub struct Get {
@@ -91,9 +94,9 @@ impl OperationBase for Get {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
         globals: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
@@ -111,12 +114,21 @@ impl OperationBase for Get {
                 }
             }
             Scope::Local(slot) => {
-                let val = stack.borrow()[slot].clone();
+                let base = call_frame.borrow().last().unwrap().base;
+                let val = stack.borrow()[base + slot].clone();
+                stack.borrow_mut().push(val);
+            }
+            Scope::Upvalue(index) => {
+                let upvalue = call_frame.borrow().last().unwrap().closure.upvalues[index].clone();
+                let val = match &*upvalue.borrow() {
+                    Upvalue::Open(slot) => stack.borrow()[*slot].clone(),
+                    Upvalue::Closed(val) => val.clone(),
+                };
                 stack.borrow_mut().push(val);
             }
         }
 
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -157,9 +169,9 @@ impl OperationBase for Set {
 
     fn eval(
         &self,
-        _: usize,
+        curr_offset: usize,
         stack: Rc<RefCell<Vec<Value>>>,
-        _: Rc<RefCell<Vec<String>>>,
+        call_frame: Rc<RefCell<Vec<CallFrame>>>,
         globals: Rc<RefCell<Table>>,
         line: usize,
     ) -> Result<usize, QalamError> {
@@ -177,11 +189,19 @@ impl OperationBase for Set {
                 }
             },
             Scope::Local(slot) => {
-                stack.borrow_mut()[slot] = val;
+                let base = call_frame.borrow().last().unwrap().base;
+                stack.borrow_mut()[base + slot] = val;
+            }
+            Scope::Upvalue(index) => {
+                let upvalue = call_frame.borrow().last().unwrap().closure.upvalues[index].clone();
+                match &mut *upvalue.borrow_mut() {
+                    Upvalue::Open(slot) => stack.borrow_mut()[*slot] = val,
+                    Upvalue::Closed(closed) => *closed = val,
+                };
             }
         }
 
-        return Ok(0);
+        return Ok(curr_offset + 1);
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
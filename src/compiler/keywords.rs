@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use super::token::TokenType;
+
+/// A spelling of the language's keywords. Every dialect maps onto the same
+/// `TokenType`s, so they can be mixed freely in one compilation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    /// Transliterated Arabic in Latin script, e.g. `qul`, `itha`.
+    Latin,
+    /// Arabic script, e.g. `قل`, `إذا`.
+    Arabic,
+    /// English keywords, e.g. `print`, `if`.
+    English,
+}
+
+/// The dialects enabled when none are selected explicitly.
+pub const DEFAULT_DIALECTS: [Dialect; 2] = [Dialect::Latin, Dialect::Arabic];
+
+const LATIN: &[(&str, TokenType)] = &[
+    ("akhiran", TokenType::FINALLY),
+    ("amal", TokenType::FUN),
+    ("aw", TokenType::OR),
+    ("batil", TokenType::FALSE),
+    ("baynama", TokenType::WHILE),
+    ("fi", TokenType::IN),
+    ("ghaib", TokenType::NIL),
+    ("haqq", TokenType::TRUE),
+    ("hawil", TokenType::TRY),
+    ("bism", TokenType::AS),
+    ("ibn", TokenType::INHERITS),
+    ("iftar", TokenType::BREAK),
+    ("illa", TokenType::ELSE),
+    ("imsak", TokenType::CATCH),
+    ("irmi", TokenType::THROW),
+    ("istawrid", TokenType::IMPORT),
+    ("itha", TokenType::IF),
+    ("kitab", TokenType::CLASS),
+    ("la", TokenType::BANG),
+    ("lazim", TokenType::CONST),
+    ("nafs", TokenType::THIS),
+    ("qul", TokenType::PRINT),
+    ("radd", TokenType::RETURN),
+    ("safar", TokenType::CONTINUE),
+    ("shai", TokenType::VAR),
+    ("tabiq", TokenType::MATCH),
+    ("tawaf", TokenType::FOR),
+    ("ulya", TokenType::SUPER),
+    ("wa", TokenType::AND),
+];
+
+// Spellings with and without hamza are both accepted, since many keyboards
+// and writers drop it.
+const ARABIC: &[(&str, TokenType)] = &[
+    ("عمل", TokenType::FUN),
+    ("أخيرا", TokenType::FINALLY),
+    ("اخيرا", TokenType::FINALLY),
+    ("حاول", TokenType::TRY),
+    ("أمسك", TokenType::CATCH),
+    ("امسك", TokenType::CATCH),
+    ("ارم", TokenType::THROW),
+    ("استورد", TokenType::IMPORT),
+    ("باسم", TokenType::AS),
+    ("في", TokenType::IN),
+    ("أو", TokenType::OR),
+    ("او", TokenType::OR),
+    ("باطل", TokenType::FALSE),
+    ("بينما", TokenType::WHILE),
+    ("غائب", TokenType::NIL),
+    ("حق", TokenType::TRUE),
+    ("ابن", TokenType::INHERITS),
+    ("إفطار", TokenType::BREAK),
+    ("افطار", TokenType::BREAK),
+    ("إلا", TokenType::ELSE),
+    ("الا", TokenType::ELSE),
+    ("إذا", TokenType::IF),
+    ("اذا", TokenType::IF),
+    ("كتاب", TokenType::CLASS),
+    ("لا", TokenType::BANG),
+    ("لازم", TokenType::CONST),
+    ("نفس", TokenType::THIS),
+    ("قل", TokenType::PRINT),
+    ("رد", TokenType::RETURN),
+    ("سفر", TokenType::CONTINUE),
+    ("شيء", TokenType::VAR),
+    ("شي", TokenType::VAR),
+    ("طابق", TokenType::MATCH),
+    ("طواف", TokenType::FOR),
+    ("عليا", TokenType::SUPER),
+    ("و", TokenType::AND),
+];
+
+const ENGLISH: &[(&str, TokenType)] = &[
+    ("fun", TokenType::FUN),
+    ("or", TokenType::OR),
+    ("false", TokenType::FALSE),
+    ("while", TokenType::WHILE),
+    ("nil", TokenType::NIL),
+    ("true", TokenType::TRUE),
+    ("extends", TokenType::INHERITS),
+    ("break", TokenType::BREAK),
+    ("else", TokenType::ELSE),
+    ("if", TokenType::IF),
+    ("class", TokenType::CLASS),
+    ("not", TokenType::BANG),
+    ("const", TokenType::CONST),
+    ("this", TokenType::THIS),
+    ("print", TokenType::PRINT),
+    ("return", TokenType::RETURN),
+    ("continue", TokenType::CONTINUE),
+    ("var", TokenType::VAR),
+    ("match", TokenType::MATCH),
+    ("for", TokenType::FOR),
+    ("super", TokenType::SUPER),
+    ("and", TokenType::AND),
+    ("throw", TokenType::THROW),
+    ("try", TokenType::TRY),
+    ("catch", TokenType::CATCH),
+    ("finally", TokenType::FINALLY),
+    ("import", TokenType::IMPORT),
+    ("as", TokenType::AS),
+    ("in", TokenType::IN),
+];
+
+impl Dialect {
+    fn keywords(&self) -> &'static [(&'static str, TokenType)] {
+        return match self {
+            Dialect::Latin => LATIN,
+            Dialect::Arabic => ARABIC,
+            Dialect::English => ENGLISH,
+        };
+    }
+}
+
+/// Keyword lookup for the dialects selected for a compilation.
+pub struct Keywords {
+    table: HashMap<&'static str, TokenType>,
+}
+
+impl Keywords {
+    pub fn new(dialects: &[Dialect]) -> Self {
+        let mut table = HashMap::new();
+        for dialect in dialects {
+            for (keyword, token_type) in dialect.keywords() {
+                table.insert(*keyword, token_type.clone());
+            }
+        }
+        return Self { table };
+    }
+
+    /// The keyword's token type, or `IDENTIFIER` if `text` is not a keyword.
+    pub fn token_type(&self, text: &str) -> TokenType {
+        return match self.table.get(text) {
+            Some(token_type) => token_type.clone(),
+            None => TokenType::IDENTIFIER,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Dialect, Keywords};
+    use crate::compiler::token::TokenType;
+
+    #[test]
+    fn test_dialects_share_token_types() {
+        let keywords = Keywords::new(&[Dialect::Latin, Dialect::Arabic]);
+        assert_eq!(keywords.token_type("qul"), TokenType::PRINT);
+        assert_eq!(keywords.token_type("قل"), TokenType::PRINT);
+        assert_eq!(keywords.token_type("awesome"), TokenType::IDENTIFIER);
+        assert_eq!(keywords.token_type("print"), TokenType::IDENTIFIER);
+        let english = Keywords::new(&[Dialect::English]);
+        assert_eq!(english.token_type("print"), TokenType::PRINT);
+        assert_eq!(english.token_type("qul"), TokenType::IDENTIFIER);
+    }
+}
@@ -1,13 +1,16 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
+use keywords::{Dialect, DEFAULT_DIALECTS};
 use parser::Parser;
 use scanner::Scanner;
 
 use crate::{
-    chunk::{pop::PopN, variable::Scope, Chunk},
+    chunk::{closure::CloseUpvalue, pop::PopN, variable::Scope, Chunk},
     error::QalamError,
+    value::function::Function,
 };
 
+pub mod keywords;
 pub mod parser;
 pub mod precedence;
 pub mod scanner;
@@ -19,6 +22,7 @@ pub struct Local {
     pub depth: usize,
     pub init: bool,
     pub immutable: bool,
+    pub captured: bool,
 }
 
 impl Local {
@@ -28,6 +32,91 @@ impl Local {
             depth,
             init,
             immutable,
+            captured: false,
+        }
+    }
+}
+
+/// A variable captured from an enclosing function, either one of its locals or one of its own upvalues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upvalue {
+    pub index: usize,
+    pub is_local: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionType {
+    Function,
+    Initializer,
+    Method,
+    Script,
+}
+
+/// An enclosing loop, for compiling 'iftar' and 'safar'.
+pub struct Loop {
+    /// Offset 'safar' jumps back to.
+    pub start: usize,
+    /// Scope depth of the loop itself; locals deeper than this belong to the body.
+    pub scope_depth: usize,
+    /// 'iftar' jumps to patch once the loop's exit is known.
+    pub breaks: Vec<usize>,
+}
+
+impl Loop {
+    pub fn new(start: usize, scope_depth: usize) -> Self {
+        Self {
+            start,
+            scope_depth,
+            breaks: Vec::new(),
+        }
+    }
+}
+
+/// Why control reached the end of a 'hawil' statement, kept in its hidden kind slot so the exit can be resumed once the 'akhiran' block has run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Completion {
+    Normal,
+    Throw,
+    Return,
+    Break,
+    Continue,
+}
+
+/// An enclosing 'hawil' statement, for routing 'radd', 'iftar' and 'safar' through its 'akhiran' block.
+pub struct TryBlock {
+    /// Scope depth of the statement's hidden slots; locals deeper than this are discarded on exit.
+    pub scope_depth: usize,
+    /// Local slot of the pending return value or error.
+    pub value_slot: usize,
+    /// Local slot of the pending `Completion`.
+    pub kind_slot: usize,
+    /// Jumps to the 'akhiran' block to patch once it starts.
+    pub exits: Vec<usize>,
+    /// Kinds of exits compiled inside the statement, which its end has to resume.
+    pub completions: Vec<Completion>,
+}
+
+impl TryBlock {
+    pub fn new(scope_depth: usize, value_slot: usize, kind_slot: usize) -> Self {
+        Self {
+            scope_depth,
+            value_slot,
+            kind_slot,
+            exits: Vec::new(),
+            completions: Vec::new(),
+        }
+    }
+}
+
+/// Tracks the class currently being compiled, so 'nafs' and 'ulya' can be validated.
+pub struct ClassCompiler {
+    pub has_superclass: bool,
+}
+
+impl ClassCompiler {
+    pub fn new() -> Self {
+        Self {
+            has_superclass: false,
         }
     }
 }
@@ -36,20 +125,62 @@ pub struct Compiler {
     pub locals: Rc<RefCell<Vec<Local>>>,
     pub local_count: usize,
     pub scope_depth: usize,
+    pub upvalues: Vec<Upvalue>,
+    pub loops: Vec<Loop>,
+    pub tries: Vec<TryBlock>,
+    pub function_type: FunctionType,
+    pub enclosing: Option<Box<Compiler>>,
+    /// Top-level declarations, mapped to whether they are 'lazim'. Only used by the script compiler.
+    pub globals: HashMap<String, bool>,
 }
 
 impl Compiler {
-    pub fn compile(stream: Vec<u8>) -> Result<Chunk, QalamError> {
-        let scanner = Scanner::new(stream);
-        let mut chunk = Chunk::new();
+    pub fn new(function_type: FunctionType) -> Self {
         let mut compiler = Self {
             locals: Rc::new(RefCell::new(Vec::new())),
             local_count: 0,
             scope_depth: 0,
+            upvalues: Vec::new(),
+            loops: Vec::new(),
+            tries: Vec::new(),
+            function_type,
+            enclosing: None,
+            globals: HashMap::new(),
+        };
+        // slot 0 holds the function being called, or the receiver for methods
+        let slot_zero = match function_type {
+            FunctionType::Method | FunctionType::Initializer => String::from("nafs"),
+            _ => String::new(),
         };
-        let parser = Parser::new(&scanner, &mut chunk, &mut compiler)?;
-        parser.parse()?;
-        return Ok(chunk);
+        compiler.add_local(slot_zero, true);
+        compiler.mark_initialized();
+        return compiler;
+    }
+
+    pub fn compile(stream: Vec<u8>) -> Result<Function, QalamError> {
+        return Self::compile_with_dialects(stream, &DEFAULT_DIALECTS);
+    }
+
+    /// Compiles with only the given keyword dialects recognised.
+    pub fn compile_with_dialects(
+        stream: Vec<u8>,
+        dialects: &[Dialect],
+    ) -> Result<Function, QalamError> {
+        return Self::compile_file(stream, dialects, "script");
+    }
+
+    /// Compiles the source of `file`, whose name is used to label anonymous functions.
+    pub fn compile_file(
+        stream: Vec<u8>,
+        dialects: &[Dialect],
+        file: &str,
+    ) -> Result<Function, QalamError> {
+        let scanner = Scanner::new(stream, dialects);
+        let parser = Parser::new(&scanner, Compiler::new(FunctionType::Script), file)?;
+        let (chunk, warnings) = parser.parse()?;
+        let mut function = Function::new(String::from("__main__"), 0, chunk);
+        function.warnings = warnings;
+        return Ok(function);
     }
 
     pub fn add_local(&mut self, name: String, immutable: bool) {
@@ -59,24 +190,86 @@ impl Compiler {
         self.local_count += 1;
     }
 
+    pub fn add_global(&mut self, name: String, immutable: bool) {
+        match &mut self.enclosing {
+            Some(enclosing) => enclosing.add_global(name, immutable),
+            None => {
+                // redeclaring a global is allowed, the latest declaration decides mutability
+                self.globals.insert(name, immutable);
+            }
+        }
+    }
+
+    fn is_immutable_global(&self, name: &String) -> bool {
+        match &self.enclosing {
+            Some(enclosing) => return enclosing.is_immutable_global(name),
+            None => return *self.globals.get(name).unwrap_or(&false),
+        }
+    }
+
     pub fn begin_scope(&mut self) {
         self.scope_depth += 1;
     }
 
-    pub fn resolve_local(&self, name: String, line: usize) -> Result<(Scope, bool), QalamError> {
+    fn find_local(&self, name: &String, line: usize) -> Result<Option<(usize, bool)>, QalamError> {
         for i in (0..self.local_count).rev() {
             let local = &self.locals.borrow()[i];
-            if local.name == name {
+            if local.name == *name {
                 if !local.init {
                     return Err(QalamError::with_line_compile(
                         "Can't read local variable in it's own initializer.",
                         line,
                     ));
                 }
-                return Ok((Scope::Local(i), local.immutable));
+                return Ok(Some((i, local.immutable)));
             }
         }
-        return Ok((Scope::Global, false));
+        return Ok(None);
+    }
+
+    fn add_upvalue(&mut self, index: usize, is_local: bool) -> usize {
+        let upvalue = Upvalue { index, is_local };
+        if let Some(existing) = self.upvalues.iter().position(|u| *u == upvalue) {
+            return existing;
+        }
+        self.upvalues.push(upvalue);
+        return self.upvalues.len() - 1;
+    }
+
+    fn resolve_upvalue(
+        &mut self,
+        name: &String,
+        line: usize,
+    ) -> Result<Option<(usize, bool)>, QalamError> {
+        let enclosing = match &mut self.enclosing {
+            Some(enclosing) => enclosing,
+            None => return Ok(None),
+        };
+        let (index, is_local, immutable) =
+            if let Some((slot, immutable)) = enclosing.find_local(name, line)? {
+                enclosing.locals.borrow_mut()[slot].captured = true;
+                (slot, true, immutable)
+            } else if let Some((index, immutable)) = enclosing.resolve_upvalue(name, line)? {
+                (index, false, immutable)
+            } else {
+                return Ok(None);
+            };
+        return Ok(Some((self.add_upvalue(index, is_local), immutable)));
+    }
+
+    pub fn resolve_local(
+        &mut self,
+        name: String,
+        line: usize,
+    ) -> Result<(Scope, bool), QalamError> {
+        if let Some((slot, immutable)) = self.find_local(&name, line)? {
+            return Ok((Scope::Local(slot), immutable));
+        }
+        if let Some((index, immutable)) = self.resolve_upvalue(&name, line)? {
+            return Ok((Scope::Upvalue(index), immutable));
+        }
+        let immutable = self.is_immutable_global(&name);
+        return Ok((Scope::Global, immutable));
     }
 
     pub fn mark_initialized(&mut self) {
@@ -86,7 +279,7 @@ impl Compiler {
 
     pub fn end_scope<'b>(&mut self, chunk: &'b mut Chunk, line: usize) {
         self.scope_depth -= 1;
-        let mut pop_count = 0;
+        self.discard_locals(self.scope_depth, chunk, line);
         loop {
             if self.local_count == 0 {
                 break;
@@ -97,8 +290,173 @@ impl Compiler {
             }
             self.locals.borrow_mut().pop();
             self.local_count -= 1;
-            pop_count += 1;
         }
-        chunk.write(Box::new(PopN::new(pop_count)), line);
+    }
+
+    /// Emits the operations removing every local deeper than `depth` from the stack, without forgetting them.
+    pub fn discard_locals<'b>(&self, depth: usize, chunk: &'b mut Chunk, line: usize) {
+        let mut pop_count = 0;
+        for i in (0..self.local_count).rev() {
+            let local = &self.locals.borrow()[i];
+            if local.depth <= depth {
+                break;
+            }
+            if local.captured {
+                // captured locals are moved off the stack one at a time, so flush the plain pops above it first
+                if pop_count > 0 {
+                    chunk.write(Box::new(PopN::new(pop_count)), line);
+                    pop_count = 0;
+                }
+                chunk.write(Box::new(CloseUpvalue::new()), line);
+            } else {
+                pop_count += 1;
+            }
+        }
+        if pop_count > 0 {
+            chunk.write(Box::new(PopN::new(pop_count)), line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compiler::keywords::DEFAULT_DIALECTS;
+    use crate::compiler::Compiler;
+
+    fn compile(src: &str) -> Result<(), String> {
+        match Compiler::compile(Vec::<u8>::from(format!("{}\n", src))) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(format!("{}", e)),
+        }
+    }
+
+    #[test]
+    fn test_return_statement() {
+        assert!(compile("amal f(a) { radd a + 1; } qul f(1);").is_ok());
+        assert!(compile("amal f() { radd; }").is_ok());
+        assert!(compile("radd;").is_ok());
+        let err = compile("radd 1;").unwrap_err();
+        assert!(err.starts_with("CompileError: Can't return a value from top-level code."));
+    }
+
+    #[test]
+    fn test_global_redeclaration() {
+        assert!(compile("shai x = 1; shai x = 2; amal f() {} amal f() {}").is_ok());
+    }
+
+    #[test]
+    fn test_inheritance_errors() {
+        assert!(compile("kitab A {} kitab B ibn A { f() { radd ulya.f; } }").is_ok());
+        let err = compile("kitab A ibn A {}").unwrap_err();
+        assert!(err.starts_with("CompileError: A class can't inherit from itself."));
+        let err = compile("kitab A { f() { ulya.f(); } }").unwrap_err();
+        assert!(err.starts_with("CompileError: Can't use 'ulya' in a class with no superclass."));
+        let err = compile("ulya.f();").unwrap_err();
+        assert!(err.starts_with("CompileError: Can't use 'ulya' outside of a class."));
+    }
+
+    #[test]
+    fn test_loop_control() {
+        assert!(compile("baynama (haqq) { shai x = 1; itha (x) iftar; safar; }").is_ok());
+        assert!(compile("tawaf (shai i = 0; i < 3; i++) { safar; }").is_ok());
+        let err = compile("iftar;").unwrap_err();
+        assert!(err.starts_with("CompileError: Can't use 'iftar' outside of a loop."));
+        let err = compile("baynama (haqq) { amal f() { safar; } }").unwrap_err();
+        assert!(err.starts_with("CompileError: Can't use 'safar' outside of a loop."));
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert!(compile(r#"qul "a\n\t\\\"\u{628}";"#).is_ok());
+        assert!(compile(r#"shai x = 1; qul "x = ${x + 1} and ${"${x}"}";"#).is_ok());
+        let err = compile(r#"qul "\q";"#).unwrap_err();
+        assert!(err.starts_with(r"SyntaxError: Invalid escape sequence '\q'."));
+        let err = compile(r#"qul "\u{}";"#).unwrap_err();
+        assert!(err.starts_with("SyntaxError: Invalid unicode escape"));
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        assert!(compile("shai اسم = 1; amal جمع(أ, ب) { radd أ + ب; } qul جمع(اسم, 2);").is_ok());
+        let err = Compiler::compile(b"shai x = \"a\xff\";\n".to_vec())
+            .err()
+            .unwrap();
+        assert!(format!("{}", err).starts_with("SyntaxError: Invalid UTF-8 sequence."));
+    }
+
+    #[test]
+    fn test_number_literals() {
+        assert!(compile("qul 0xFF + 0b1010 + 0o17 + 1.5e-3 + 1_000_000 + ١٢٫٥;").is_ok());
+        let err = compile("qul 0x;").unwrap_err();
+        assert!(err.starts_with(
+            "SyntaxError: Expect digits after number prefix.\n\tat line 1\n\tat '0x'"
+        ));
+        let err = compile("qul 1e;").unwrap_err();
+        assert!(err.starts_with("SyntaxError: Expect digits in number exponent."));
+        let err = compile("qul 1__0;").unwrap_err();
+        assert!(err.starts_with("SyntaxError: Misplaced '_' in number literal."));
+    }
+
+    #[test]
+    fn test_conditional() {
+        assert!(compile("shai x = haqq ? 1 : batil ? 2 : 3;").is_ok());
+        let err = compile("qul haqq ? 1;").unwrap_err();
+        assert!(err
+            .starts_with("CompileError: Expect ':' after then branch of conditional expression."));
+    }
+
+    #[test]
+    fn test_null_operators() {
+        assert!(compile("shai a; shai b = a ?? a?.x.y ?? a?.(1) ?? a?.[0]; b ??= 1;").is_ok());
+        let err = compile("shai a; a?.b = 1;").unwrap_err();
+        assert!(err.starts_with("CompileError: Invalid assignment target."));
+    }
+
+    #[test]
+    fn test_for_in() {
+        assert!(
+            compile("tawaf (shai x fi [1, 2]) qul x; tawaf (shai i fi 0..=9) { iftar; }").is_ok()
+        );
+        let err = compile("tawaf (shai x fi) qul x;").unwrap_err();
+        assert!(err.starts_with("CompileError: Expect expression."));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        assert!(compile("shai sq = amal (x) { radd x * x; }; shai add = (a, b) => a + b;").is_ok());
+        assert!(compile("amal (x) { qul x; }(1); shai k = () => (1); qul (k);").is_ok());
+        let function = Compiler::compile_file(
+            Vec::from("\nshai f = (x) => x;\n"),
+            &DEFAULT_DIALECTS,
+            "a.q",
+        );
+        let chunk = format!("{}", function.ok().unwrap().chunk);
+        assert!(chunk.contains("<amal a.q:2>"));
+    }
+
+    #[test]
+    fn test_match_statement() {
+        assert!(compile(
+            "tabiq (3) { 0 => qul 0; -1..=9 => qul 1; \"s\" => {} n itha n > 9 => qul n; _ => {} }"
+        )
+        .is_ok());
+        let err = compile("tabiq (3) { 0..x => qul 0; }").unwrap_err();
+        assert!(
+            err.starts_with("SyntaxError: Expect literal, range, '_' or name as match pattern.")
+        );
+        let src = "tabiq (3) {\n_ => qul 0;\n1 => qul 1;\nn => qul n;\n}\n";
+        let function = Compiler::compile(Vec::<u8>::from(src)).unwrap();
+        let warnings = function
+            .warnings
+            .iter()
+            .map(|warning| format!("{}", warning))
+            .collect::<Vec<String>>();
+        assert_eq!(
+            warnings,
+            [
+                "Warning: Unreachable match arm after a catch-all pattern.\n\tat line 3\n\tat '1'",
+                "Warning: Unreachable match arm after a catch-all pattern.\n\tat line 4\n\tat 'n'",
+            ]
+        );
     }
 }
@@ -1,51 +1,117 @@
 use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
 
 use crate::chunk::binary::Binary;
 use crate::chunk::binary::BinaryOp;
+use crate::chunk::call::Call;
+use crate::chunk::class::ClassOp;
+use crate::chunk::class::GetSuper;
+use crate::chunk::class::Inherit;
+use crate::chunk::class::Method;
+use crate::chunk::closure::ClosureOp;
 use crate::chunk::constant::Constant;
+use crate::chunk::exception::EndTry;
+use crate::chunk::exception::Throw;
+use crate::chunk::exception::Try;
+use crate::chunk::import::Import;
+use crate::chunk::index::GetIndex;
+use crate::chunk::index::SetIndex;
+use crate::chunk::iterate::IterNext;
+use crate::chunk::iterate::RangeNext;
 use crate::chunk::jump::FalseJump;
 use crate::chunk::jump::Jump;
 use crate::chunk::jump::LoopJump;
+use crate::chunk::jump::NullJump;
+use crate::chunk::list::BuildList;
+use crate::chunk::map::BuildMap;
 use crate::chunk::operation::Operation;
+use crate::chunk::pattern::MatchRange;
 use crate::chunk::pop::Pop;
 use crate::chunk::print::Print;
+use crate::chunk::property::GetProperty;
+use crate::chunk::property::SetProperty;
 use crate::chunk::return_op::ReturnOp;
 use crate::chunk::unary::Unary;
 use crate::chunk::unary::UnaryOp;
 use crate::chunk::variable::Define;
 use crate::chunk::variable::Get;
+use crate::chunk::variable::Scope;
 use crate::chunk::variable::Set;
 use crate::error::QalamError;
+use crate::value::bigint::BigInt;
+use crate::value::function::Function;
+use crate::value::numerals;
 use crate::value::Value;
+use unicode_xid::UnicodeXID;
 
 use super::precedence::Precedence;
 use super::token::Token;
 use super::token::TokenType;
 use super::Chunk;
+use super::ClassCompiler;
 use super::Compiler;
+use super::Completion;
+use super::FunctionType;
+use super::Loop;
 use super::Scanner;
+use super::TryBlock;
+use super::Upvalue;
+
+const ASSIGNMENT_TOKENS: [TokenType; 14] = [
+    TokenType::EQUAL,
+    TokenType::INCREMENT,
+    TokenType::DECREMENT,
+    TokenType::PLUS_EQUAL,
+    TokenType::MINUS_EQUAL,
+    TokenType::STAR_EQUAL,
+    TokenType::SLASH_EQUAL,
+    TokenType::STAR_STAR_EQUAL,
+    TokenType::AMPERSAND_EQUAL,
+    TokenType::PIPE_EQUAL,
+    TokenType::CARET_EQUAL,
+    TokenType::LESS_LESS_EQUAL,
+    TokenType::GREATER_GREATER_EQUAL,
+    TokenType::QUESTION_QUESTION_EQUAL,
+];
 
 pub struct Parser<'a> {
     scanner: &'a Scanner<'a>,
-    chunk: RefCell<&'a mut Chunk>,
+    chunk: RefCell<Chunk>,
     current: RefCell<Token<'a>>,
     previous: RefCell<Option<Token<'a>>>,
-    compiler: RefCell<&'a mut Compiler>,
+    compiler: RefCell<Compiler>,
+    class_compilers: RefCell<Vec<ClassCompiler>>,
+    /// Pending `?.` jumps of each expression being parsed, patched to skip the
+    /// rest of the access chain when the receiver is `ghaib`.
+    optional_chains: RefCell<Vec<Vec<usize>>>,
+    /// Name of the source file, for naming anonymous functions.
+    file: String,
+    /// Set while compiling the top level of a 'tabiq' guard, where `(x) =>` ends the guard rather than starting an
+    /// arrow function. Brackets and function bodies clear it, since `=>` can't end the guard inside them.
+    in_guard: RefCell<bool>,
+    /// Warnings found so far, returned alongside the chunk.
+    warnings: RefCell<Vec<QalamError>>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(
         scanner: &'a Scanner<'a>,
-        chunk: &'a mut Chunk,
-        compiler: &'a mut Compiler,
+        compiler: Compiler,
+        file: &str,
     ) -> Result<Self, QalamError> {
         let curr = scanner.scan()?;
         return Ok(Self {
             scanner,
-            chunk: RefCell::new(chunk),
+            chunk: RefCell::new(Chunk::new()),
             current: RefCell::new(curr),
             previous: RefCell::new(None),
             compiler: RefCell::new(compiler),
+            class_compilers: RefCell::new(Vec::new()),
+            optional_chains: RefCell::new(Vec::new()),
+            file: file.to_string(),
+            in_guard: RefCell::new(false),
+            warnings: RefCell::new(Vec::new()),
         });
     }
 
@@ -74,34 +140,155 @@ impl<'a> Parser<'a> {
                 self.emit_op(Constant::new(Value::Null));
             }
             TokenType::STRING => {
-                if let Some((_, rest)) = prev.literal.split_first() {
-                    if let Some((_, mid)) = rest.split_last() {
-                        let string = std::str::from_utf8(mid).unwrap().to_string();
-                        self.emit_op(Constant::new(Value::String(string)));
-                    }
-                }
-                // let string = std::str::from_utf8(&prev.literal).unwrap().to_string();
-                // self.emit_op(Constant::new(Value::String(string)));
+                let string = self.string_contents(&prev, 1)?;
+                self.emit_op(Constant::new(Value::String(string)));
             }
             _ => {}
         };
         return Ok(());
     }
 
+    /// Compiles `"a ${x} b"` into `"a " + str(x) + " b"`.
+    pub fn interpolation(&self, _: bool) -> Result<(), QalamError> {
+        let mut first = true;
+        loop {
+            let prev = self.previous.borrow().as_ref().unwrap().clone();
+            let string = self.string_contents(&prev, 2)?;
+            self.emit_op(Constant::new(Value::String(string)));
+            if !first {
+                self.emit_op(Binary::new(BinaryOp::Add));
+            }
+            first = false;
+
+            self.expression()?;
+            self.emit_op(Unary::new(UnaryOp::Stringify));
+            self.emit_op(Binary::new(BinaryOp::Add));
+
+            if !self.match_token(TokenType::INTERPOLATION)? {
+                break;
+            }
+        }
+        self.consume(
+            TokenType::STRING,
+            "Expect '}' after interpolated expression.",
+        )?;
+        let prev = self.previous.borrow().as_ref().unwrap().clone();
+        let string = self.string_contents(&prev, 1)?;
+        if !string.is_empty() {
+            self.emit_op(Constant::new(Value::String(string)));
+            self.emit_op(Binary::new(BinaryOp::Add));
+        }
+        return Ok(());
+    }
+
+    /// Strips the delimiters off a string token (the opening `"` or `}` and
+    /// `tail` closing bytes) and decodes its escape sequences.
+    fn string_contents(&self, token: &Token, tail: usize) -> Result<String, QalamError> {
+        let lexeme = token.lexeme()?;
+        let raw = &lexeme[1..lexeme.len() - tail];
+        let mut string = String::with_capacity(raw.len());
+        let mut chars = raw.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                string.push(c);
+                continue;
+            }
+            let decoded = match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('0') => '\0',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                Some('$') => '$',
+                Some('u') => self.unicode_escape(&mut chars, token)?,
+                Some(other) => {
+                    return Err(QalamError::from_token_syntax(
+                        &format!("Invalid escape sequence '\\{}'.", other),
+                        token,
+                    ))
+                }
+                None => {
+                    return Err(QalamError::from_token_syntax(
+                        "Unfinished escape sequence.",
+                        token,
+                    ))
+                }
+            };
+            string.push(decoded);
+        }
+        return Ok(string);
+    }
+
+    /// Decodes the `{XXXX}` part of a `\u{XXXX}` escape.
+    fn unicode_escape(
+        &self,
+        chars: &mut std::str::Chars,
+        token: &Token,
+    ) -> Result<char, QalamError> {
+        let error = || {
+            return QalamError::from_token_syntax(
+                "Invalid unicode escape, expected '\\u{' followed by 1 to 6 hex digits and '}'.",
+                token,
+            );
+        };
+        if chars.next() != Some('{') {
+            return Err(error());
+        }
+        let mut digits = String::new();
+        loop {
+            match chars.next() {
+                Some('}') => break,
+                Some(c) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+                _ => return Err(error()),
+            }
+        }
+        if digits.is_empty() {
+            return Err(error());
+        }
+        let code = u32::from_str_radix(&digits, 16).unwrap();
+        return match char::from_u32(code) {
+            Some(c) => Ok(c),
+            None => Err(QalamError::from_token_syntax(
+                &format!("Invalid unicode code point '{}'.", digits),
+                token,
+            )),
+        };
+    }
+
     pub fn grouping(&self, _: bool) -> Result<(), QalamError> {
-        self.expression()?;
+        if self.arrow_ahead()? {
+            let (function, upvalues) =
+                self.function_body(self.lambda_name(), FunctionType::Function, true)?;
+            self.emit_op(ClosureOp::new(Rc::new(function), upvalues));
+            return Ok(());
+        }
+        self.outside_guard(|| self.expression())?;
         self.consume(TokenType::RIGHT_PAREN, "Expect ')' after expression.")?;
         return Ok(());
     }
 
+    /// Runs `parse` with arrow functions allowed again, for code nested inside a guard's brackets.
+    fn outside_guard<T>(
+        &self,
+        parse: impl FnOnce() -> Result<T, QalamError>,
+    ) -> Result<T, QalamError> {
+        let in_guard = self.in_guard.replace(false);
+        let result = parse();
+        self.in_guard.replace(in_guard);
+        return result;
+    }
+
     pub fn unary(&self, _: bool) -> Result<(), QalamError> {
         let op_type = self.previous.borrow().as_ref().unwrap().clone().token_type;
 
-        self.parse_precedence(Precedence::Unary)?;
+        // the operand takes in `**`, so `-2 ** 2` is `-(2 ** 2)`
+        self.parse_precedence(Precedence::Pow)?;
 
         match op_type {
             TokenType::MINUS => self.emit_op(Unary::new(UnaryOp::Negate)),
             TokenType::BANG => self.emit_op(Unary::new(UnaryOp::Bang)),
+            TokenType::TILDE => self.emit_op(Unary::new(UnaryOp::BitNot)),
             _ => {}
         };
         return Ok(());
@@ -111,7 +298,12 @@ impl<'a> Parser<'a> {
         let op_type = self.previous.borrow().as_ref().unwrap().clone().token_type;
         let rule = Precedence::get_rule(op_type.clone());
 
-        let next_prec = rule.precedence + 1;
+        // `**` is right associative, so its right operand may be another `**`
+        let next_prec = if op_type == TokenType::STAR_STAR {
+            Some(rule.precedence)
+        } else {
+            rule.precedence + 1
+        };
         self.parse_precedence(next_prec.unwrap())?;
 
         match op_type {
@@ -127,6 +319,27 @@ impl<'a> Parser<'a> {
             TokenType::SLASH => {
                 self.emit_op(Binary::new(BinaryOp::Div));
             }
+            TokenType::TILDE_SLASH => {
+                self.emit_op(Binary::new(BinaryOp::IntDiv));
+            }
+            TokenType::STAR_STAR => {
+                self.emit_op(Binary::new(BinaryOp::Pow));
+            }
+            TokenType::AMPERSAND => {
+                self.emit_op(Binary::new(BinaryOp::BitAnd));
+            }
+            TokenType::PIPE => {
+                self.emit_op(Binary::new(BinaryOp::BitOr));
+            }
+            TokenType::CARET => {
+                self.emit_op(Binary::new(BinaryOp::BitXor));
+            }
+            TokenType::LESS_LESS => {
+                self.emit_op(Binary::new(BinaryOp::ShiftLeft));
+            }
+            TokenType::GREATER_GREATER => {
+                self.emit_op(Binary::new(BinaryOp::ShiftRight));
+            }
             TokenType::PERCENT => {
                 self.emit_op(Binary::new(BinaryOp::Modulo));
             }
@@ -157,6 +370,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_precedence(&self, precedence: Precedence) -> Result<(), QalamError> {
+        self.optional_chains.borrow_mut().push(Vec::new());
         self.advance()?;
         let prev = self.previous.borrow().as_ref().unwrap().clone();
         let prefix_rule = Precedence::get_rule(prev.token_type.clone()).prefix;
@@ -172,8 +386,12 @@ impl<'a> Parser<'a> {
         {
             self.advance()?;
             let prev = self.previous.borrow().as_ref().unwrap().clone();
-            let infix_rule = Precedence::get_rule(prev.token_type.clone()).infix;
-            if let Some(infix_rule) = infix_rule {
+            let rule = Precedence::get_rule(prev.token_type.clone());
+            if rule.precedence < Precedence::Call {
+                // anything looser than an access ends the chain
+                self.end_optional_chain();
+            }
+            if let Some(infix_rule) = rule.infix {
                 let can_assign = precedence <= Precedence::Assignment;
                 infix_rule(self, can_assign)?;
                 if can_assign && self.match_token(TokenType::EQUAL)? {
@@ -185,9 +403,18 @@ impl<'a> Parser<'a> {
             }
         }
 
+        self.end_optional_chain();
+        self.optional_chains.borrow_mut().pop();
         return Ok(());
     }
 
+    fn end_optional_chain(&self) {
+        let jumps = std::mem::take(self.optional_chains.borrow_mut().last_mut().unwrap());
+        for jump in jumps {
+            self.patch_jump(jump);
+        }
+    }
+
     fn advance(&self) -> Result<(), QalamError> {
         let next = self.scanner.scan()?;
         self.previous
@@ -201,9 +428,20 @@ impl<'a> Parser<'a> {
     }
 
     fn emit_return(&self) {
+        self.emit_return_value();
         self.emit_op(ReturnOp::new());
     }
 
+    /// Pushes what a bare 'radd' returns.
+    fn emit_return_value(&self) {
+        if self.compiler.borrow().function_type == FunctionType::Initializer {
+            // initializers always hand back the new instance
+            self.emit_op(Get::new(String::from("nafs"), Scope::Local(0)));
+        } else {
+            self.emit_op(Constant::new(Value::Null));
+        }
+    }
+
     fn expression(&self) -> Result<(), QalamError> {
         self.parse_precedence(Precedence::Assignment)?;
         return Ok(());
@@ -211,10 +449,29 @@ impl<'a> Parser<'a> {
 
     pub fn number(&self, _: bool) -> Result<(), QalamError> {
         let prev = self.previous.borrow().as_ref().unwrap().clone();
-        match std::str::from_utf8(&prev.literal).unwrap().parse::<f64>() {
-            Ok(num) => self.emit_op(Constant::new(Value::Number(num))),
-            Err(_) => {
-                return Err(QalamError::from_token_compile("Invalid number.", &prev));
+        // the scanner has already validated the literal's syntax
+        let text = numerals::normalize(prev.lexeme()?).replace('_', "");
+        let radix = match text.get(0..2) {
+            Some("0x" | "0X") => 16,
+            Some("0b" | "0B") => 2,
+            Some("0o" | "0O") => 8,
+            _ => 10,
+        };
+        // a fraction or exponent makes a float, anything else is an integer
+        let num = if radix != 10 {
+            BigInt::parse(&text[2..], radix).map(Value::from_big)
+        } else if text.contains(['.', 'e', 'E']) {
+            text.parse::<f64>().ok().map(Value::Number)
+        } else {
+            BigInt::parse(&text, 10).map(Value::from_big)
+        };
+        match num {
+            Some(num) => self.emit_op(Constant::new(num)),
+            None => {
+                return Err(QalamError::from_token_syntax(
+                    "Number literal is too large.",
+                    &prev,
+                ));
             }
         }
 
@@ -249,6 +506,40 @@ impl<'a> Parser<'a> {
         return Ok(());
     }
 
+    fn return_statement(&self) -> Result<(), QalamError> {
+        if self.match_token(TokenType::SEMICOLON)? {
+            self.emit_return_value();
+            self.emit_return_op();
+            return Ok(());
+        }
+        match self.compiler.borrow().function_type {
+            FunctionType::Script => {
+                return Err(QalamError::from_token_compile(
+                    "Can't return a value from top-level code.",
+                    self.previous.clone().borrow().as_ref().unwrap(),
+                ));
+            }
+            FunctionType::Initializer => {
+                return Err(QalamError::from_token_compile(
+                    "Can't return a value from an initializer.",
+                    self.previous.clone().borrow().as_ref().unwrap(),
+                ));
+            }
+            _ => {}
+        }
+        self.expression()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after return value.")?;
+        self.emit_return_op();
+        return Ok(());
+    }
+
+    /// Returns the value on top of the stack, running any enclosing 'akhiran' blocks first.
+    fn emit_return_op(&self) {
+        if !self.finally_exit(Completion::Return, 0) {
+            self.emit_op(ReturnOp::new());
+        }
+    }
+
     fn expression_statement(&self) -> Result<(), QalamError> {
         self.expression()?;
         self.consume(TokenType::SEMICOLON, "Expect ';' after value.")?;
@@ -270,26 +561,9 @@ impl<'a> Parser<'a> {
         return self.chunk.borrow().count - 1;
     }
 
-    fn patch_false_jump(&self, jump: usize) {
-        let offset = self.chunk.borrow().count - 1 - jump; // how much to jump
-        let mut chunk = self.chunk.borrow_mut();
-
-        let jump = &mut chunk.code[jump];
-        let false_jump_op = jump.as_any_mut().downcast_mut::<FalseJump>();
-        if let Some(false_jump_op) = false_jump_op {
-            false_jump_op.jump = Some(offset);
-        }
-    }
-
     fn patch_jump(&self, jump: usize) {
         let offset = self.chunk.borrow().count - 1 - jump; // how much to jump
-        let mut chunk = self.chunk.borrow_mut();
-
-        let jump = &mut chunk.code[jump];
-        let jump_op = jump.as_any_mut().downcast_mut::<Jump>();
-        if let Some(jump_op) = jump_op {
-            jump_op.jump = Some(offset);
-        }
+        self.chunk.borrow_mut().code[jump].patch_jump(offset);
     }
 
     pub fn and(&self, _: bool) -> Result<(), QalamError> {
@@ -297,20 +571,71 @@ impl<'a> Parser<'a> {
         self.emit_op(Pop::new());
 
         self.parse_precedence(Precedence::And)?;
-        self.patch_false_jump(end_jump);
+        self.patch_jump(end_jump);
         return Ok(());
     }
 
     pub fn or(&self, _: bool) -> Result<(), QalamError> {
         let else_jump = self.emit_jump(FalseJump::new());
         let end_jump = self.emit_jump(Jump::new());
-        self.patch_false_jump(else_jump);
+        self.patch_jump(else_jump);
         self.emit_op(Pop::new());
         self.parse_precedence(Precedence::Or)?;
         self.patch_jump(end_jump);
         return Ok(());
     }
 
+    /// Compiles `cond ? a : b`, evaluating only the selected branch.
+    pub fn conditional(&self, _: bool) -> Result<(), QalamError> {
+        let else_jump = self.emit_jump(FalseJump::new());
+        self.emit_op(Pop::new());
+        self.parse_precedence(Precedence::Conditional)?;
+        let end_jump = self.emit_jump(Jump::new());
+
+        self.patch_jump(else_jump);
+        self.emit_op(Pop::new());
+        self.consume(
+            TokenType::COLON,
+            "Expect ':' after then branch of conditional expression.",
+        )?;
+        // right associative, so `a ? b : c ? d : e` nests in the else branch
+        self.parse_precedence(Precedence::Conditional)?;
+        self.patch_jump(end_jump);
+        return Ok(());
+    }
+
+    /// Compiles `a ?? b`, evaluating `b` only when `a` is `ghaib`.
+    pub fn coalesce(&self, _: bool) -> Result<(), QalamError> {
+        let else_jump = self.emit_jump(NullJump::new());
+        let end_jump = self.emit_jump(Jump::new());
+        self.patch_jump(else_jump);
+        self.emit_op(Pop::new());
+        self.parse_precedence(Precedence::Coalesce)?;
+        self.patch_jump(end_jump);
+        return Ok(());
+    }
+
+    /// Compiles `obj?.field`, `f?.(args)` and `xs?.[i]`. A `ghaib` receiver
+    /// skips the rest of the chain and becomes its result.
+    pub fn optional(&self, _: bool) -> Result<(), QalamError> {
+        let jump = self.emit_jump(NullJump::new());
+        self.optional_chains
+            .borrow_mut()
+            .last_mut()
+            .unwrap()
+            .push(jump);
+        if self.match_token(TokenType::LEFT_PAREN)? {
+            self.call(false)?;
+        } else if self.match_token(TokenType::LEFT_BRACKET)? {
+            self.index(false)?;
+        } else {
+            self.consume(TokenType::IDENTIFIER, "Expect property name after '?.'.")?;
+            let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+            self.emit_op(GetProperty::new(name));
+        }
+        return Ok(());
+    }
+
     fn if_statement(&self) -> Result<(), QalamError> {
         self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'itha'.")?;
         self.expression()?;
@@ -321,7 +646,7 @@ impl<'a> Parser<'a> {
         self.statement()?;
         let else_jump = self.emit_jump(Jump::new());
 
-        self.patch_false_jump(then_jump);
+        self.patch_jump(then_jump);
         self.emit_op(Pop::new());
         if self.match_token(TokenType::ELSE)? {
             self.statement()?;
@@ -333,28 +658,274 @@ impl<'a> Parser<'a> {
     }
 
     fn emit_loop(&self, start: usize) {
-        let jump = self.chunk.borrow().count - 1 - start;
+        let jump = self.chunk.borrow().count - start;
         self.emit_op(LoopJump::new(jump));
     }
 
     fn while_statement(&self) -> Result<(), QalamError> {
-        let loop_start = self.chunk.borrow().count - 1;
+        let loop_start = self.chunk.borrow().count;
         self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'baynama'.")?;
         self.expression()?;
         self.consume(TokenType::RIGHT_PAREN, "Expect ')' after condition.")?;
 
         let exit_jump = self.emit_jump(FalseJump::new());
         self.emit_op(Pop::new());
-        self.statement()?;
+        self.loop_body(loop_start)?;
         self.emit_loop(loop_start);
-        self.patch_false_jump(exit_jump);
+        self.patch_jump(exit_jump);
         self.emit_op(Pop::new());
+        self.patch_breaks();
+        return Ok(());
+    }
+
+    fn loop_body(&self, start: usize) -> Result<(), QalamError> {
+        let depth = self.compiler.borrow().scope_depth;
+        self.compiler
+            .borrow_mut()
+            .loops
+            .push(Loop::new(start, depth));
+        self.statement()?;
         return Ok(());
     }
 
+    fn patch_breaks(&self) {
+        let enclosing_loop = self.compiler.borrow_mut().loops.pop().unwrap();
+        for jump in enclosing_loop.breaks {
+            self.patch_jump(jump);
+        }
+    }
+
+    fn loop_depth(&self, keyword: &str) -> Result<usize, QalamError> {
+        match self.compiler.borrow().loops.last() {
+            Some(enclosing_loop) => return Ok(enclosing_loop.scope_depth),
+            None => {
+                return Err(QalamError::from_token_compile(
+                    &format!("Can't use '{}' outside of a loop.", keyword),
+                    self.previous.clone().borrow().as_ref().unwrap(),
+                ))
+            }
+        }
+    }
+
+    fn break_statement(&self) -> Result<(), QalamError> {
+        let depth = self.loop_depth("iftar")?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after 'iftar'.")?;
+        self.emit_break(depth);
+        return Ok(());
+    }
+
+    fn emit_break(&self, depth: usize) {
+        if self.finally_exit(Completion::Break, depth) {
+            return;
+        }
+        self.compiler.borrow().discard_locals(
+            depth,
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        let jump = self.emit_jump(Jump::new());
+        self.compiler
+            .borrow_mut()
+            .loops
+            .last_mut()
+            .unwrap()
+            .breaks
+            .push(jump);
+    }
+
+    fn continue_statement(&self) -> Result<(), QalamError> {
+        let depth = self.loop_depth("safar")?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after 'safar'.")?;
+        self.emit_continue(depth);
+        return Ok(());
+    }
+
+    fn emit_continue(&self, depth: usize) {
+        if self.finally_exit(Completion::Continue, depth) {
+            return;
+        }
+        self.compiler.borrow().discard_locals(
+            depth,
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        let start = self.compiler.borrow().loops.last().unwrap().start;
+        self.emit_loop(start);
+    }
+
+    fn throw_statement(&self) -> Result<(), QalamError> {
+        self.expression()?;
+        self.consume(TokenType::SEMICOLON, "Expect ';' after thrown value.")?;
+        self.emit_op(Throw::new());
+        return Ok(());
+    }
+
+    fn try_statement(&self) -> Result<(), QalamError> {
+        // hidden slots for the pending return value or error, and why the 'akhiran' block was entered
+        self.compiler.borrow_mut().begin_scope();
+        let depth = self.compiler.borrow().scope_depth;
+        self.emit_op(Constant::new(Value::Null));
+        self.compiler.borrow_mut().add_local(String::new(), true);
+        self.compiler.borrow_mut().mark_initialized();
+        self.emit_op(Constant::new(Value::Int(Completion::Normal as i64)));
+        self.compiler.borrow_mut().add_local(String::new(), true);
+        self.compiler.borrow_mut().mark_initialized();
+        let kind_slot = self.compiler.borrow().local_count - 1;
+        self.compiler
+            .borrow_mut()
+            .tries
+            .push(TryBlock::new(depth, kind_slot - 1, kind_slot));
+
+        let handler = self.emit_jump(Try::new());
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' after 'hawil'.")?;
+        self.scoped_block()?;
+        self.emit_op(EndTry::new());
+        self.finally_jump();
+        self.patch_jump(handler);
+
+        let has_catch = self.match_token(TokenType::CATCH)?;
+        if has_catch {
+            // the handler left the error where the next local goes
+            self.compiler.borrow_mut().begin_scope();
+            let mut name = String::new();
+            if self.match_token(TokenType::LEFT_PAREN)? {
+                self.consume(TokenType::IDENTIFIER, "Expect error name after '('.")?;
+                name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+                self.consume(TokenType::RIGHT_PAREN, "Expect ')' after error name.")?;
+            }
+            self.compiler.borrow_mut().add_local(name, false);
+            self.compiler.borrow_mut().mark_initialized();
+            let binding = self.compiler.borrow().local_count - 1;
+            let handler = self.emit_jump(Try::new());
+            self.consume(TokenType::LEFT_BRACE, "Expect '{' after 'imsak'.")?;
+            self.block()?;
+            self.emit_op(EndTry::new());
+            self.compiler.borrow_mut().end_scope(
+                &mut self.chunk.borrow_mut(),
+                self.previous.clone().borrow().as_ref().unwrap().line,
+            );
+            self.finally_jump();
+            self.patch_jump(handler);
+            // an error thrown by the body lands above the binding, so move it down into the binding's slot to leave
+            // the stack as high as when there's no 'imsak'
+            self.emit_op(Set::new(String::new(), Scope::Local(binding)));
+            self.emit_op(Pop::new());
+        }
+
+        // an uncaught error is kept until the 'akhiran' block has run
+        self.emit_op(Set::new(String::new(), Scope::Local(kind_slot - 1)));
+        self.emit_op(Pop::new());
+        self.set_completion(kind_slot, Completion::Throw);
+
+        let block = self.compiler.borrow_mut().tries.pop().unwrap();
+        for jump in block.exits {
+            self.patch_jump(jump);
+        }
+        if self.match_token(TokenType::FINALLY)? {
+            self.consume(TokenType::LEFT_BRACE, "Expect '{' after 'akhiran'.")?;
+            self.scoped_block()?;
+        } else if !has_catch {
+            return Err(QalamError::from_token_syntax(
+                "Expect 'imsak' or 'akhiran' after 'hawil' block.",
+                &self.current.borrow(),
+            ));
+        }
+
+        // resume whatever left the statement
+        let loop_depth = self.compiler.borrow().loops.last().map(|l| l.scope_depth);
+        for completion in [Completion::Throw].into_iter().chain(block.completions) {
+            self.emit_op(Get::new(String::new(), Scope::Local(kind_slot)));
+            self.emit_op(Constant::new(Value::Int(completion as i64)));
+            self.emit_op(Binary::new(BinaryOp::Equal));
+            let skip = self.emit_jump(FalseJump::new());
+            self.emit_op(Pop::new());
+            match completion {
+                Completion::Throw | Completion::Return => {
+                    self.emit_op(Get::new(String::new(), Scope::Local(kind_slot - 1)));
+                    if completion == Completion::Throw {
+                        self.emit_op(Throw::new());
+                    } else {
+                        self.emit_return_op();
+                    }
+                }
+                Completion::Break => self.emit_break(loop_depth.unwrap()),
+                Completion::Continue => self.emit_continue(loop_depth.unwrap()),
+                Completion::Normal => {}
+            }
+            self.patch_jump(skip);
+            self.emit_op(Pop::new());
+        }
+        self.compiler.borrow_mut().end_scope(
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        return Ok(());
+    }
+
+    fn scoped_block(&self) -> Result<(), QalamError> {
+        self.compiler.borrow_mut().begin_scope();
+        self.block()?;
+        self.compiler.borrow_mut().end_scope(
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        return Ok(());
+    }
+
+    fn set_completion(&self, kind_slot: usize, completion: Completion) {
+        self.emit_op(Constant::new(Value::Int(completion as i64)));
+        self.emit_op(Set::new(String::new(), Scope::Local(kind_slot)));
+        self.emit_op(Pop::new());
+    }
+
+    /// Jumps to the 'akhiran' block of the innermost 'hawil' statement.
+    fn finally_jump(&self) {
+        let jump = self.emit_jump(Jump::new());
+        self.compiler
+            .borrow_mut()
+            .tries
+            .last_mut()
+            .unwrap()
+            .exits
+            .push(jump);
+    }
+
+    /// Routes an exit through the 'akhiran' block of the innermost 'hawil' statement deeper than `depth`, if there is one. A returned value is expected on top of the stack.
+    fn finally_exit(&self, completion: Completion, depth: usize) -> bool {
+        let (scope_depth, value_slot, kind_slot) = match self.compiler.borrow().tries.last() {
+            Some(block) if block.scope_depth > depth => {
+                (block.scope_depth, block.value_slot, block.kind_slot)
+            }
+            _ => return false,
+        };
+        self.emit_op(EndTry::new());
+        if completion == Completion::Return {
+            self.emit_op(Set::new(String::new(), Scope::Local(value_slot)));
+            self.emit_op(Pop::new());
+        }
+        self.set_completion(kind_slot, completion);
+        self.compiler.borrow().discard_locals(
+            scope_depth,
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        self.finally_jump();
+        let mut compiler = self.compiler.borrow_mut();
+        let completions = &mut compiler.tries.last_mut().unwrap().completions;
+        if !completions.contains(&completion) {
+            completions.push(completion);
+        }
+        return true;
+    }
+
     fn for_statement(&self) -> Result<(), QalamError> {
         self.compiler.borrow_mut().begin_scope();
         self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'tawaf'.")?;
+        if self.check_token(TokenType::VAR)
+            && self.scanner.lookahead(2)? == [TokenType::IDENTIFIER, TokenType::IN]
+        {
+            return self.for_in_statement();
+        }
         // self.consume(TokenType::SEMICOLON, "Expect ';'.")?;
         if self.match_token(TokenType::SEMICOLON)? {
             // no initializer
@@ -364,7 +935,7 @@ impl<'a> Parser<'a> {
             self.expression_statement()?;
         }
 
-        let mut loop_start = self.chunk.borrow().count - 1;
+        let mut loop_start = self.chunk.borrow().count;
         let mut exit_jump = None;
         if !self.match_token(TokenType::SEMICOLON)? {
             self.expression()?;
@@ -376,7 +947,7 @@ impl<'a> Parser<'a> {
 
         if !self.match_token(TokenType::RIGHT_PAREN)? {
             let body_jump = self.emit_jump(Jump::new());
-            let inc_start = self.chunk.borrow().count - 1;
+            let inc_start = self.chunk.borrow().count;
             self.expression()?;
             self.emit_op(Pop::new());
             self.consume(TokenType::RIGHT_PAREN, "Expect ')' after tawaf clauses.")?;
@@ -385,13 +956,192 @@ impl<'a> Parser<'a> {
             self.patch_jump(body_jump);
         }
 
-        self.statement()?;
+        self.loop_body(loop_start)?;
 
         self.emit_loop(loop_start);
         if let Some(exit_jump) = exit_jump {
-            self.patch_false_jump(exit_jump);
+            self.patch_jump(exit_jump);
             self.emit_op(Pop::new());
         }
+        self.patch_breaks();
+        self.compiler.borrow_mut().end_scope(
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        return Ok(());
+    }
+
+    fn match_statement(&self) -> Result<(), QalamError> {
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after 'tabiq'.")?;
+        self.compiler.borrow_mut().begin_scope();
+        self.expression()?;
+        // the subject lives in a hidden local so every arm can reload it
+        self.compiler.borrow_mut().add_local(String::new(), true);
+        self.compiler.borrow_mut().mark_initialized();
+        let subject = self.compiler.borrow().local_count - 1;
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after value.")?;
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' before match arms.")?;
+
+        let mut end_jumps = Vec::new();
+        let mut catch_all = false;
+        while !self.check_token(TokenType::RIGHT_BRACE) && !self.check_token(TokenType::EOF) {
+            if catch_all {
+                self.warnings
+                    .borrow_mut()
+                    .push(QalamError::from_token_warning(
+                        "Unreachable match arm after a catch-all pattern.",
+                        &self.current.borrow(),
+                    ));
+            }
+            self.compiler.borrow_mut().begin_scope();
+            let (mut next_jumps, binds) = self.match_pattern(subject)?;
+            let guarded = self.match_token(TokenType::IF)?;
+            if guarded {
+                self.in_guard.replace(true);
+                let guard = self.expression();
+                self.in_guard.replace(false);
+                guard?;
+                next_jumps.push(self.emit_jump(FalseJump::new()));
+                self.emit_op(Pop::new());
+            } else if next_jumps.is_empty() {
+                catch_all = true;
+            }
+            self.consume(TokenType::FAT_ARROW, "Expect '=>' after match pattern.")?;
+            self.statement()?;
+            self.compiler.borrow_mut().end_scope(
+                &mut self.chunk.borrow_mut(),
+                self.previous.clone().borrow().as_ref().unwrap().line,
+            );
+            end_jumps.push(self.emit_jump(Jump::new()));
+
+            // a failed test leaves its condition, and any binding, on the stack
+            if !next_jumps.is_empty() {
+                for jump in next_jumps {
+                    self.patch_jump(jump);
+                }
+                self.emit_op(Pop::new());
+                if binds {
+                    self.emit_op(Pop::new());
+                }
+            }
+            self.match_token(TokenType::COMMA)?;
+        }
+        self.consume(TokenType::RIGHT_BRACE, "Expect '}' after match arms.")?;
+
+        for jump in end_jumps {
+            self.patch_jump(jump);
+        }
+        self.compiler.borrow_mut().end_scope(
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        return Ok(());
+    }
+
+    /// Compiles the tests of one match arm against the subject in local slot `subject`, returning the jumps taken when it doesn't match and whether it bound a new local.
+    fn match_pattern(&self, subject: usize) -> Result<(Vec<usize>, bool), QalamError> {
+        let get_subject = || self.emit_op(Get::new(String::new(), Scope::Local(subject)));
+        if self.match_token(TokenType::IDENTIFIER)? {
+            let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+            if name == "_" {
+                return Ok((Vec::new(), false));
+            }
+            get_subject();
+            self.compiler.borrow_mut().add_local(name, false);
+            self.compiler.borrow_mut().mark_initialized();
+            return Ok((Vec::new(), true));
+        }
+
+        get_subject();
+        self.match_literal()?;
+        if self.match_tokens(&[TokenType::DOT_DOT, TokenType::DOT_DOT_EQUAL])? {
+            let inclusive =
+                self.previous.borrow().as_ref().unwrap().token_type == TokenType::DOT_DOT_EQUAL;
+            self.match_literal()?;
+            self.emit_op(MatchRange::new(inclusive));
+        } else {
+            self.emit_op(Binary::new(BinaryOp::Equal));
+        }
+        let jump = self.emit_jump(FalseJump::new());
+        self.emit_op(Pop::new());
+        return Ok((vec![jump], false));
+    }
+
+    fn match_literal(&self) -> Result<(), QalamError> {
+        let negate = self.match_token(TokenType::MINUS)?;
+        if negate && !self.check_token(TokenType::NUMBER) {
+            return Err(QalamError::from_token_syntax(
+                "Expect number after '-' in pattern.",
+                &self.current.borrow(),
+            ));
+        }
+        match self.current.borrow().token_type {
+            TokenType::NUMBER
+            | TokenType::STRING
+            | TokenType::TRUE
+            | TokenType::FALSE
+            | TokenType::NIL => {}
+            _ => {
+                return Err(QalamError::from_token_syntax(
+                    "Expect literal, range, '_' or name as match pattern.",
+                    &self.current.borrow(),
+                ))
+            }
+        }
+        self.parse_precedence(Precedence::Primary)?;
+        if negate {
+            self.emit_op(Unary::new(UnaryOp::Negate));
+        }
+        return Ok(());
+    }
+
+    /// Compiles the rest of `tawaf (shai x fi iterable) body`, with the loop's scope already begun.
+    fn for_in_statement(&self) -> Result<(), QalamError> {
+        self.consume(TokenType::VAR, "Expect 'shai'.")?;
+        self.consume(TokenType::IDENTIFIER, "Expect variable name.")?;
+        let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+        self.consume(TokenType::IN, "Expect 'fi' after loop variable.")?;
+
+        // hidden slots: the iterable and its cursor, or a range's counter and bound
+        self.expression()?;
+        self.compiler.borrow_mut().add_local(String::new(), true);
+        self.compiler.borrow_mut().mark_initialized();
+        let slot = self.compiler.borrow().local_count - 1;
+        let mut range = None;
+        if self.match_tokens(&[TokenType::DOT_DOT, TokenType::DOT_DOT_EQUAL])? {
+            range = Some(
+                self.previous.borrow().as_ref().unwrap().token_type == TokenType::DOT_DOT_EQUAL,
+            );
+            self.expression()?;
+        } else {
+            self.emit_op(Constant::new(Value::Int(0)));
+        }
+        self.compiler.borrow_mut().add_local(String::new(), true);
+        self.compiler.borrow_mut().mark_initialized();
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after tawaf clauses.")?;
+
+        let loop_start = self.chunk.borrow().count;
+        let exit_jump = match range {
+            Some(inclusive) => self.emit_jump(RangeNext::new(slot, inclusive)),
+            None => self.emit_jump(IterNext::new(slot)),
+        };
+        // the item is a fresh local each iteration, so closures capture its own value
+        let depth = self.compiler.borrow().scope_depth;
+        self.compiler
+            .borrow_mut()
+            .loops
+            .push(Loop::new(loop_start, depth));
+        self.compiler.borrow_mut().begin_scope();
+        self.compiler.borrow_mut().add_local(name, false);
+        self.compiler.borrow_mut().mark_initialized();
+        self.statement()?;
+        self.compiler.borrow_mut().end_scope(
+            &mut self.chunk.borrow_mut(),
+            self.previous.clone().borrow().as_ref().unwrap().line,
+        );
+        self.emit_loop(loop_start);
+        self.patch_jump(exit_jump);
+        self.patch_breaks();
         self.compiler.borrow_mut().end_scope(
             &mut self.chunk.borrow_mut(),
             self.previous.clone().borrow().as_ref().unwrap().line,
@@ -406,8 +1156,22 @@ impl<'a> Parser<'a> {
             self.for_statement()?;
         } else if self.match_token(TokenType::IF)? {
             self.if_statement()?;
+        } else if self.match_token(TokenType::RETURN)? {
+            self.return_statement()?;
+        } else if self.match_token(TokenType::BREAK)? {
+            self.break_statement()?;
+        } else if self.match_token(TokenType::CONTINUE)? {
+            self.continue_statement()?;
         } else if self.match_token(TokenType::WHILE)? {
             self.while_statement()?;
+        } else if self.match_token(TokenType::MATCH)? {
+            self.match_statement()?;
+        } else if self.match_token(TokenType::THROW)? {
+            self.throw_statement()?;
+        } else if self.match_token(TokenType::TRY)? {
+            self.try_statement()?;
+        } else if self.map_ahead()? {
+            self.expression_statement()?;
         } else if self.match_token(TokenType::LEFT_BRACE)? {
             self.compiler.borrow_mut().begin_scope();
             self.block()?;
@@ -423,15 +1187,16 @@ impl<'a> Parser<'a> {
     }
 
     fn identifier_string(&self, name: Token) -> Result<String, QalamError> {
-        return Ok(std::str::from_utf8(name.literal).unwrap().to_string());
+        return Ok(name.lexeme()?.to_string());
     }
 
     fn declare_variable(&self, immutable: bool) -> Result<(), QalamError> {
+        let prev = self.previous.clone().borrow().as_ref().unwrap().clone();
+        let name = prev.lexeme()?.to_string();
         if self.compiler.borrow().scope_depth == 0 {
+            self.compiler.borrow_mut().add_global(name, immutable);
             return Ok(());
         }
-        let prev = self.previous.clone().borrow().as_ref().unwrap().clone();
-        let name = std::str::from_utf8(&prev.literal).unwrap().to_string();
         {
             let compiler = self.compiler.borrow();
             for i in (0..compiler.local_count).rev() {
@@ -463,22 +1228,12 @@ impl<'a> Parser<'a> {
 
     fn named_variable(&self, name: Token, can_assign: bool) -> Result<(), QalamError> {
         let id = self.identifier_string(name)?;
-        let (scope, immutable) = self.compiler.borrow().resolve_local(
+        let (scope, immutable) = self.compiler.borrow_mut().resolve_local(
             id.clone(),
             self.previous.clone().borrow().as_ref().unwrap().line,
         )?;
 
-        if can_assign
-            && self.match_tokens(&[
-                TokenType::EQUAL,
-                TokenType::INCREMENT,
-                TokenType::DECREMENT,
-                TokenType::PLUS_EQUAL,
-                TokenType::MINUS_EQUAL,
-                TokenType::STAR_EQUAL,
-                TokenType::SLASH_EQUAL,
-            ])?
-        {
+        if can_assign && self.match_tokens(&ASSIGNMENT_TOKENS)? {
             if immutable {
                 return Err(QalamError::from_token_compile(
                     &format!(
@@ -488,42 +1243,7 @@ impl<'a> Parser<'a> {
                     self.previous.clone().borrow().as_ref().unwrap(),
                 ));
             }
-            match self.previous.clone().borrow().as_ref().unwrap().token_type {
-                TokenType::EQUAL => {
-                    self.expression()?;
-                }
-                TokenType::INCREMENT => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.emit_op(Constant::new(Value::Number(1.0)));
-                    self.emit_op(Binary::new(BinaryOp::Add));
-                }
-                TokenType::DECREMENT => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.emit_op(Constant::new(Value::Number(1.0)));
-                    self.emit_op(Binary::new(BinaryOp::Subtract));
-                }
-                TokenType::PLUS_EQUAL => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.expression()?;
-                    self.emit_op(Binary::new(BinaryOp::Add))
-                }
-                TokenType::MINUS_EQUAL => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.expression()?;
-                    self.emit_op(Binary::new(BinaryOp::Subtract))
-                }
-                TokenType::STAR_EQUAL => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.expression()?;
-                    self.emit_op(Binary::new(BinaryOp::Mult))
-                }
-                TokenType::SLASH_EQUAL => {
-                    self.emit_op(Get::new(id.clone(), scope.clone()));
-                    self.expression()?;
-                    self.emit_op(Binary::new(BinaryOp::Div))
-                }
-                _ => {}
-            };
+            self.assignment_value(&|| self.emit_op(Get::new(id.clone(), scope.clone())))?;
             self.emit_op(Set::new(id, scope));
         } else {
             self.emit_op(Get::new(id, scope));
@@ -531,6 +1251,88 @@ impl<'a> Parser<'a> {
         return Ok(());
     }
 
+    /// Compiles the value of an assignment whose operator was just matched, calling `get` to load the target's current value for the compound forms.
+    fn assignment_value(&self, get: &dyn Fn()) -> Result<(), QalamError> {
+        match self.previous.clone().borrow().as_ref().unwrap().token_type {
+            TokenType::EQUAL => {
+                self.expression()?;
+            }
+            TokenType::INCREMENT => {
+                get();
+                self.emit_op(Constant::new(Value::Int(1)));
+                self.emit_op(Binary::new(BinaryOp::Add));
+            }
+            TokenType::DECREMENT => {
+                get();
+                self.emit_op(Constant::new(Value::Int(1)));
+                self.emit_op(Binary::new(BinaryOp::Subtract));
+            }
+            TokenType::PLUS_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::Add))
+            }
+            TokenType::MINUS_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::Subtract))
+            }
+            TokenType::STAR_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::Mult))
+            }
+            TokenType::SLASH_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::Div))
+            }
+            TokenType::QUESTION_QUESTION_EQUAL => {
+                // only evaluates the value when the target is `ghaib`; otherwise
+                // the target's own value is written back
+                get();
+                let else_jump = self.emit_jump(NullJump::new());
+                let end_jump = self.emit_jump(Jump::new());
+                self.patch_jump(else_jump);
+                self.emit_op(Pop::new());
+                self.expression()?;
+                self.patch_jump(end_jump);
+            }
+            TokenType::STAR_STAR_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::Pow))
+            }
+            TokenType::AMPERSAND_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::BitAnd))
+            }
+            TokenType::PIPE_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::BitOr))
+            }
+            TokenType::CARET_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::BitXor))
+            }
+            TokenType::LESS_LESS_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::ShiftLeft))
+            }
+            TokenType::GREATER_GREATER_EQUAL => {
+                get();
+                self.expression()?;
+                self.emit_op(Binary::new(BinaryOp::ShiftRight))
+            }
+            _ => {}
+        };
+        return Ok(());
+    }
+
     pub fn variable(&self, can_assign: bool) -> Result<(), QalamError> {
         self.named_variable(
             self.previous.clone().borrow().as_ref().unwrap().clone(),
@@ -565,11 +1367,397 @@ impl<'a> Parser<'a> {
         return Ok(());
     }
 
+    pub fn call(&self, _: bool) -> Result<(), QalamError> {
+        let arg_count = self.outside_guard(|| self.argument_list())?;
+        self.emit_op(Call::new(arg_count));
+        return Ok(());
+    }
+
+    fn argument_list(&self) -> Result<usize, QalamError> {
+        let mut arg_count = 0;
+        if !self.check_token(TokenType::RIGHT_PAREN) {
+            loop {
+                self.expression()?;
+                if arg_count == 255 {
+                    return Err(QalamError::from_token_compile(
+                        "Can't have more than 255 arguments.",
+                        self.previous.clone().borrow().as_ref().unwrap(),
+                    ));
+                }
+                arg_count += 1;
+                if !self.match_token(TokenType::COMMA)? {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after arguments.")?;
+        return Ok(arg_count);
+    }
+
+    fn function(
+        &self,
+        name: String,
+        function_type: FunctionType,
+    ) -> Result<(Function, Vec<Upvalue>), QalamError> {
+        self.consume(TokenType::LEFT_PAREN, "Expect '(' after function name.")?;
+        return self.function_body(name, function_type, false);
+    }
+
+    /// Compiles a function's parameters and body, starting after the '('. An arrow body is a single expression whose value is returned.
+    fn function_body(
+        &self,
+        name: String,
+        function_type: FunctionType,
+        arrow: bool,
+    ) -> Result<(Function, Vec<Upvalue>), QalamError> {
+        // compile the body into a fresh chunk with its own locals, restoring the enclosing ones after
+        let enclosing_chunk = self.chunk.replace(Chunk::new());
+        let enclosing = self.compiler.replace(Compiler::new(function_type));
+        self.compiler.borrow_mut().enclosing = Some(Box::new(enclosing));
+        self.compiler.borrow_mut().begin_scope();
+
+        let mut arity = 0;
+        if !self.check_token(TokenType::RIGHT_PAREN) {
+            loop {
+                if arity == 255 {
+                    return Err(QalamError::from_token_compile(
+                        "Can't have more than 255 parameters.",
+                        &self.current.borrow(),
+                    ));
+                }
+                arity += 1;
+                let param = self.parse_variable(false)?;
+                self.define_variable(param)?;
+                if !self.match_token(TokenType::COMMA)? {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenType::RIGHT_PAREN, "Expect ')' after parameters.")?;
+        if arrow {
+            self.consume(TokenType::FAT_ARROW, "Expect '=>' after parameters.")?;
+            self.expression()?;
+            self.emit_op(ReturnOp::new());
+        } else {
+            self.consume(TokenType::LEFT_BRACE, "Expect '{' before function body.")?;
+            self.block()?;
+            self.emit_return();
+        }
+
+        let chunk = self.chunk.replace(enclosing_chunk);
+        let enclosing = self.compiler.borrow_mut().enclosing.take().unwrap();
+        let compiled = self.compiler.replace(*enclosing);
+        return Ok((Function::new(name, arity, chunk), compiled.upvalues));
+    }
+
+    /// Compiles an anonymous `amal (params) { body }` expression.
+    pub fn lambda(&self, _: bool) -> Result<(), QalamError> {
+        let (function, upvalues) =
+            self.outside_guard(|| self.function(self.lambda_name(), FunctionType::Function))?;
+        self.emit_op(ClosureOp::new(Rc::new(function), upvalues));
+        return Ok(());
+    }
+
+    /// The name given to an anonymous function starting at the previous token: `file:line`.
+    fn lambda_name(&self) -> String {
+        let line = self.previous.borrow().as_ref().unwrap().line;
+        return format!("{}:{}", self.file, line);
+    }
+
+    /// Whether the '(' just consumed starts the parameters of `(params) => body`.
+    fn arrow_ahead(&self) -> Result<bool, QalamError> {
+        if *self.in_guard.borrow() {
+            return Ok(false);
+        }
+        if self.check_token(TokenType::RIGHT_PAREN) {
+            return Ok(self.scanner.lookahead(1)? == [TokenType::FAT_ARROW]);
+        }
+        if !self.check_token(TokenType::IDENTIFIER) {
+            return Ok(false);
+        }
+        // the tokens after the current one must be `(, name)* ) =>`
+        let mut count = 2;
+        loop {
+            let ahead = self.scanner.lookahead(count)?;
+            match (&ahead[count - 2], &ahead[count - 1]) {
+                (TokenType::RIGHT_PAREN, next) => return Ok(*next == TokenType::FAT_ARROW),
+                (TokenType::COMMA, TokenType::IDENTIFIER) => count += 2,
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    fn fun_declaration(&self) -> Result<(), QalamError> {
+        let global = self.parse_variable(false)?;
+        let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+        // a local function can refer to itself in its body
+        if self.compiler.borrow().scope_depth > 0 {
+            self.compiler.borrow_mut().mark_initialized();
+        }
+        let (function, upvalues) = self.function(name, FunctionType::Function)?;
+        self.emit_op(ClosureOp::new(Rc::new(function), upvalues));
+        self.define_variable(global)?;
+        return Ok(());
+    }
+
+    pub fn list(&self, _: bool) -> Result<(), QalamError> {
+        let mut count = 0;
+        while !self.check_token(TokenType::RIGHT_BRACKET) {
+            self.outside_guard(|| self.expression())?;
+            count += 1;
+            if !self.match_token(TokenType::COMMA)? {
+                break;
+            }
+        }
+        self.consume(TokenType::RIGHT_BRACKET, "Expect ']' after list elements.")?;
+        self.emit_op(BuildList::new(count));
+        return Ok(());
+    }
+
+    pub fn map(&self, _: bool) -> Result<(), QalamError> {
+        let mut count = 0;
+        while !self.check_token(TokenType::RIGHT_BRACE) {
+            self.outside_guard(|| self.expression())?;
+            self.consume(TokenType::COLON, "Expect ':' after map key.")?;
+            self.outside_guard(|| self.expression())?;
+            count += 1;
+            if !self.match_token(TokenType::COMMA)? {
+                break;
+            }
+        }
+        self.consume(TokenType::RIGHT_BRACE, "Expect '}' after map entries.")?;
+        self.emit_op(BuildMap::new(count));
+        return Ok(());
+    }
+
+    /// Whether the '{' at the start of a statement opens a map literal rather than a block, i.e. it reads `{key:`.
+    fn map_ahead(&self) -> Result<bool, QalamError> {
+        if !self.check_token(TokenType::LEFT_BRACE) {
+            return Ok(false);
+        }
+        let ahead = self.scanner.lookahead(2)?;
+        let is_key = match ahead[0] {
+            TokenType::STRING
+            | TokenType::NUMBER
+            | TokenType::TRUE
+            | TokenType::FALSE
+            | TokenType::IDENTIFIER => true,
+            _ => false,
+        };
+        return Ok(is_key && ahead[1] == TokenType::COLON);
+    }
+
+    pub fn index(&self, can_assign: bool) -> Result<(), QalamError> {
+        self.outside_guard(|| self.expression())?;
+        self.consume(TokenType::RIGHT_BRACKET, "Expect ']' after index.")?;
+        if can_assign && self.match_tokens(&ASSIGNMENT_TOKENS)? {
+            // compound forms read the element while leaving the target and index for the write
+            let operator = self.previous.borrow().as_ref().unwrap().token_type.clone();
+            if operator == TokenType::QUESTION_QUESTION_EQUAL {
+                self.assignment_value(&|| self.emit_op(GetIndex::optional()))?;
+            } else {
+                self.assignment_value(&|| self.emit_op(GetIndex::new(true)))?;
+            }
+            self.emit_op(SetIndex::new());
+        } else {
+            self.emit_op(GetIndex::new(false));
+        }
+        return Ok(());
+    }
+
+    pub fn dot(&self, can_assign: bool) -> Result<(), QalamError> {
+        self.consume(TokenType::IDENTIFIER, "Expect property name after '.'.")?;
+        let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+        if can_assign && self.match_token(TokenType::EQUAL)? {
+            self.expression()?;
+            self.emit_op(SetProperty::new(name));
+        } else {
+            self.emit_op(GetProperty::new(name));
+        }
+        return Ok(());
+    }
+
+    pub fn this(&self, _: bool) -> Result<(), QalamError> {
+        if self.class_compilers.borrow().is_empty() {
+            return Err(QalamError::from_token_compile(
+                "Can't use 'nafs' outside of a class.",
+                self.previous.clone().borrow().as_ref().unwrap(),
+            ));
+        }
+        // the keyword may be spelled in any dialect, the slot is always "nafs"
+        let line = self.previous.borrow().as_ref().unwrap().line;
+        self.named_variable(Token::new(TokenType::THIS, "nafs".as_bytes(), line), false)?;
+        return Ok(());
+    }
+
+    pub fn super_(&self, _: bool) -> Result<(), QalamError> {
+        let keyword = self.previous.borrow().as_ref().unwrap().clone();
+        match self.class_compilers.borrow().last() {
+            None => {
+                return Err(QalamError::from_token_compile(
+                    "Can't use 'ulya' outside of a class.",
+                    &keyword,
+                ));
+            }
+            Some(class_compiler) if !class_compiler.has_superclass => {
+                return Err(QalamError::from_token_compile(
+                    "Can't use 'ulya' in a class with no superclass.",
+                    &keyword,
+                ));
+            }
+            _ => {}
+        }
+        self.consume(TokenType::DOT, "Expect '.' after 'ulya'.")?;
+        self.consume(TokenType::IDENTIFIER, "Expect superclass method name.")?;
+        let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+
+        self.named_variable(
+            Token::new(TokenType::THIS, "nafs".as_bytes(), keyword.line),
+            false,
+        )?;
+        self.named_variable(
+            Token::new(TokenType::SUPER, "ulya".as_bytes(), keyword.line),
+            false,
+        )?;
+        self.emit_op(GetSuper::new(name));
+        return Ok(());
+    }
+
+    fn method(&self) -> Result<(), QalamError> {
+        self.consume(TokenType::IDENTIFIER, "Expect method name.")?;
+        let name = self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?;
+        let function_type = if name == "init" {
+            FunctionType::Initializer
+        } else {
+            FunctionType::Method
+        };
+        let (function, upvalues) = self.function(name.clone(), function_type)?;
+        self.emit_op(ClosureOp::new(Rc::new(function), upvalues));
+        self.emit_op(Method::new(name));
+        return Ok(());
+    }
+
+    fn class_declaration(&self) -> Result<(), QalamError> {
+        self.consume(TokenType::IDENTIFIER, "Expect class name.")?;
+        let class_name = self.previous.borrow().as_ref().unwrap().clone();
+        let name = self.identifier_string(class_name.clone())?;
+        self.declare_variable(false)?;
+        let global = if self.compiler.borrow().scope_depth > 0 {
+            String::new()
+        } else {
+            name.clone()
+        };
+
+        self.emit_op(ClassOp::new(name.clone()));
+        self.define_variable(global)?;
+
+        self.class_compilers.borrow_mut().push(ClassCompiler::new());
+        if self.match_token(TokenType::INHERITS)? {
+            self.consume(TokenType::IDENTIFIER, "Expect superclass name.")?;
+            let superclass = self.previous.borrow().as_ref().unwrap().clone();
+            if self.identifier_string(superclass.clone())? == name {
+                return Err(QalamError::from_token_compile(
+                    "A class can't inherit from itself.",
+                    &superclass,
+                ));
+            }
+            self.variable(false)?;
+
+            // the superclass lives in a local named 'ulya' that methods capture
+            self.compiler.borrow_mut().begin_scope();
+            self.compiler
+                .borrow_mut()
+                .add_local(String::from("ulya"), true);
+            self.define_variable(String::new())?;
+
+            self.named_variable(class_name.clone(), false)?;
+            self.emit_op(Inherit::new());
+            self.class_compilers
+                .borrow_mut()
+                .last_mut()
+                .unwrap()
+                .has_superclass = true;
+        }
+
+        // keep the class on the stack while its methods are bound to it
+        self.named_variable(class_name, false)?;
+        self.consume(TokenType::LEFT_BRACE, "Expect '{' before class body.")?;
+        while !self.check_token(TokenType::RIGHT_BRACE) && !self.check_token(TokenType::EOF) {
+            self.method()?;
+        }
+        self.consume(TokenType::RIGHT_BRACE, "Expect '}' after class body.")?;
+        self.emit_op(Pop::new());
+
+        let class_compiler = self.class_compilers.borrow_mut().pop().unwrap();
+        if class_compiler.has_superclass {
+            self.compiler.borrow_mut().end_scope(
+                &mut self.chunk.borrow_mut(),
+                self.previous.clone().borrow().as_ref().unwrap().line,
+            );
+        }
+        return Ok(());
+    }
+
+    /// Compiles `istawrid "path" bism name;`, binding the module to a 'lazim' global named after the file unless `bism` names it.
+    fn import_declaration(&self) -> Result<(), QalamError> {
+        let keyword = self.previous.borrow().as_ref().unwrap().clone();
+        if self.compiler.borrow().function_type != FunctionType::Script
+            || self.compiler.borrow().scope_depth > 0
+        {
+            return Err(QalamError::from_token_compile(
+                "Can only use 'istawrid' at top level.",
+                &keyword,
+            ));
+        }
+        self.consume(TokenType::STRING, "Expect module path after 'istawrid'.")?;
+        let path_token = self.previous.borrow().as_ref().unwrap().clone();
+        let path = self.string_contents(&path_token, 1)?;
+        let name = if self.match_token(TokenType::AS)? {
+            self.consume(TokenType::IDENTIFIER, "Expect module name after 'bism'.")?;
+            self.identifier_string(self.previous.borrow().as_ref().unwrap().clone())?
+        } else {
+            let stem = Path::new(&path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mut chars = stem.chars();
+            let valid = match chars.next() {
+                Some(first) => {
+                    (first == '_' || first.is_xid_start()) && chars.all(|c| c.is_xid_continue())
+                }
+                None => false,
+            };
+            if !valid {
+                return Err(QalamError::from_token_compile(
+                    "Module file name is not a valid name, use 'bism' to name it.",
+                    &path_token,
+                ));
+            }
+            stem
+        };
+        self.consume(TokenType::SEMICOLON, "Expect ';' after import.")?;
+        self.compiler.borrow_mut().add_global(name.clone(), true);
+        self.emit_op(Import::new(path));
+        self.emit_op(Define::new(name));
+        return Ok(());
+    }
+
     pub fn declaration(&self) -> Result<(), QalamError> {
-        if self.match_token(TokenType::VAR)? {
+        if self.match_token(TokenType::CLASS)? {
+            self.class_declaration()?
+        } else if self.check_token(TokenType::FUN)
+            && self.scanner.lookahead(1)? == [TokenType::LEFT_PAREN]
+        {
+            // an anonymous function at the start of a statement, e.g. called immediately
+            self.expression_statement()?;
+        } else if self.match_token(TokenType::FUN)? {
+            self.fun_declaration()?
+        } else if self.match_token(TokenType::VAR)? {
             self.var_declaration(false)?
         } else if self.match_token(TokenType::CONST)? {
             self.var_declaration(true)?
+        } else if self.match_token(TokenType::IMPORT)? {
+            self.import_declaration()?
         } else {
             self.statement()?;
         }
@@ -577,18 +1765,14 @@ impl<'a> Parser<'a> {
         return Ok(());
     }
 
-    pub fn parse(&self) -> Result<(), QalamError> {
-        self.compiler.borrow_mut().begin_scope();
+    /// Compiles the whole script, returning its chunk and any warnings.
+    pub fn parse(self) -> Result<(Chunk, Vec<QalamError>), QalamError> {
         while !self.match_token(TokenType::EOF)? {
             self.declaration()?;
         }
-        self.compiler.borrow_mut().end_scope(
-            &mut self.chunk.borrow_mut(),
-            self.previous.clone().borrow().as_ref().unwrap().line,
-        );
         // self.consume(TokenType::EOF, "Expect end of expression.")?;
         self.emit_return();
 
-        return Ok(());
+        return Ok((self.chunk.into_inner(), self.warnings.into_inner()));
     }
 }
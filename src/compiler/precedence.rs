@@ -39,12 +39,19 @@ impl ParseRule {
 pub enum Precedence {
     None,
     Assignment,
+    Conditional,
+    Coalesce,
     Or,
     And,
     Equality,
     Comparison,
+    BitOr,
+    BitXor,
+    BitAnd,
+    Shift,
     Term,
     Factor,
+    Pow,
     Unary,
     Call,
     Primary,
@@ -56,28 +63,42 @@ impl std::ops::Add<usize> for Precedence {
         let result = match self {
             Self::None => 0,
             Self::Assignment => 1,
-            Self::Or => 2,
-            Self::And => 3,
-            Self::Equality => 4,
-            Self::Comparison => 5,
-            Self::Term => 6,
-            Self::Factor => 7,
-            Self::Unary => 8,
-            Self::Call => 9,
-            Self::Primary => 10,
+            Self::Conditional => 2,
+            Self::Coalesce => 3,
+            Self::Or => 4,
+            Self::And => 5,
+            Self::Equality => 6,
+            Self::Comparison => 7,
+            Self::BitOr => 8,
+            Self::BitXor => 9,
+            Self::BitAnd => 10,
+            Self::Shift => 11,
+            Self::Term => 12,
+            Self::Factor => 13,
+            Self::Pow => 14,
+            Self::Unary => 15,
+            Self::Call => 16,
+            Self::Primary => 17,
         } + rhs;
         match result {
             0 => Some(Self::None),
             1 => Some(Self::Assignment),
-            2 => Some(Self::Or),
-            3 => Some(Self::And),
-            4 => Some(Self::Equality),
-            5 => Some(Self::Comparison),
-            6 => Some(Self::Term),
-            7 => Some(Self::Factor),
-            8 => Some(Self::Unary),
-            9 => Some(Self::Call),
-            10 => Some(Self::Primary),
+            2 => Some(Self::Conditional),
+            3 => Some(Self::Coalesce),
+            4 => Some(Self::Or),
+            5 => Some(Self::And),
+            6 => Some(Self::Equality),
+            7 => Some(Self::Comparison),
+            8 => Some(Self::BitOr),
+            9 => Some(Self::BitXor),
+            10 => Some(Self::BitAnd),
+            11 => Some(Self::Shift),
+            12 => Some(Self::Term),
+            13 => Some(Self::Factor),
+            14 => Some(Self::Pow),
+            15 => Some(Self::Unary),
+            16 => Some(Self::Call),
+            17 => Some(Self::Primary),
             _ => None,
         }
     }
@@ -86,9 +107,24 @@ impl std::ops::Add<usize> for Precedence {
 impl Precedence {
     pub fn get_rule(token_type: TokenType) -> ParseRule {
         match token_type {
-            TokenType::LEFT_PAREN => {
-                ParseRule::only_prefix(|parser, can_assign| parser.grouping(can_assign))
+            TokenType::FUN => ParseRule::new(
+                Some(|parser, can_assign| parser.lambda(can_assign)),
+                None,
+                Precedence::None,
+            ),
+            TokenType::LEFT_PAREN => ParseRule::new(
+                Some(|parser, can_assign| parser.grouping(can_assign)),
+                Some(|parser, can_assign| parser.call(can_assign)),
+                Precedence::Call,
+            ),
+            TokenType::LEFT_BRACE => {
+                ParseRule::only_prefix(|parser, can_assign| parser.map(can_assign))
             }
+            TokenType::LEFT_BRACKET => ParseRule::new(
+                Some(|parser, can_assign| parser.list(can_assign)),
+                Some(|parser, can_assign| parser.index(can_assign)),
+                Precedence::Call,
+            ),
             TokenType::MINUS => ParseRule::new(
                 Some(|parser, can_assign| parser.unary(can_assign)),
                 Some(|parser, can_assign| parser.binary(can_assign)),
@@ -109,11 +145,73 @@ impl Precedence {
                 Some(|parser, can_assign| parser.binary(can_assign)),
                 Precedence::Factor,
             ),
+            TokenType::QUESTION => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.conditional(can_assign)),
+                Precedence::Conditional,
+            ),
+            TokenType::QUESTION_QUESTION => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.coalesce(can_assign)),
+                Precedence::Coalesce,
+            ),
+            TokenType::QUESTION_DOT => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.optional(can_assign)),
+                Precedence::Call,
+            ),
+            TokenType::AMPERSAND => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::BitAnd,
+            ),
+            TokenType::PIPE => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::BitOr,
+            ),
+            TokenType::CARET => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::BitXor,
+            ),
+            TokenType::LESS_LESS => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::Shift,
+            ),
+            TokenType::GREATER_GREATER => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::Shift,
+            ),
+            TokenType::TILDE => {
+                ParseRule::only_prefix(|parser, can_assign| parser.unary(can_assign))
+            }
+            TokenType::STAR_STAR => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::Pow,
+            ),
+            TokenType::TILDE_SLASH => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.binary(can_assign)),
+                Precedence::Factor,
+            ),
             TokenType::PERCENT => ParseRule::new(
                 None,
                 Some(|parser, can_assign| parser.binary(can_assign)),
                 Precedence::Factor,
             ),
+            TokenType::DOT => ParseRule::new(
+                None,
+                Some(|parser, can_assign| parser.dot(can_assign)),
+                Precedence::Call,
+            ),
+            TokenType::SUPER => {
+                ParseRule::only_prefix(|parser, can_assign| parser.super_(can_assign))
+            }
+            TokenType::THIS => ParseRule::only_prefix(|parser, can_assign| parser.this(can_assign)),
             TokenType::NUMBER => {
                 ParseRule::only_prefix(|parser, can_assign| parser.number(can_assign))
             }
@@ -162,6 +260,9 @@ impl Precedence {
             TokenType::STRING => {
                 ParseRule::only_prefix(|parser, can_assign| parser.literal(can_assign))
             }
+            TokenType::INTERPOLATION => {
+                ParseRule::only_prefix(|parser, can_assign| parser.interpolation(can_assign))
+            }
             TokenType::IDENTIFIER => {
                 ParseRule::only_prefix(|parser, can_assign| parser.variable(can_assign))
             }
@@ -1,23 +1,31 @@
+use super::keywords::{Dialect, Keywords};
 use super::token::{Token, TokenType};
 use std::{cell::RefCell, marker::PhantomData};
+use unicode_xid::UnicodeXID;
 
 use crate::error::QalamError;
+use crate::value::numerals;
 
 pub struct Scanner<'a> {
     stream: Vec<u8>,
     current: RefCell<usize>,
     start: RefCell<usize>,
     line: RefCell<usize>,
+    /// Open brace count of each `${` interpolation being scanned.
+    interpolations: RefCell<Vec<usize>>,
+    keywords: Keywords,
     phantom: PhantomData<&'a ()>,
 }
 
 impl<'a> Scanner<'a> {
-    pub fn new(stream: Vec<u8>) -> Self {
+    pub fn new(stream: Vec<u8>, dialects: &[Dialect]) -> Self {
         Self {
             stream,
             current: RefCell::new(0),
             start: RefCell::new(0),
             line: RefCell::new(1),
+            interpolations: RefCell::new(Vec::new()),
+            keywords: Keywords::new(dialects),
             phantom: PhantomData,
         }
     }
@@ -34,20 +42,55 @@ impl<'a> Scanner<'a> {
         )
     }
 
+    /// Decodes the code point starting at byte `offset`, along with its
+    /// width in bytes, or `None` if the bytes there are not valid UTF-8.
+    fn decode(&self, offset: usize) -> Option<(char, usize)> {
+        let width = match self.stream[offset] {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return None,
+        };
+        let end = std::cmp::min(offset + width, self.stream.len());
+        let c = std::str::from_utf8(&self.stream[offset..end])
+            .ok()?
+            .chars()
+            .next()?;
+        return Some((c, width));
+    }
+
+    fn invalid_utf8(&self) -> QalamError {
+        return QalamError::with_line_syntax("Invalid UTF-8 sequence.", *self.line.borrow());
+    }
+
     fn advance(&self) -> char {
-        let c = self.stream[*self.current.borrow()];
+        let (c, width) = self
+            .decode(*self.current.borrow())
+            .unwrap_or((char::REPLACEMENT_CHARACTER, 1));
         if *self.current.borrow() < self.stream.len() {
-            self.current.replace_with(|&mut old| old + 1);
+            self.current.replace_with(|&mut old| old + width);
         }
-        return c as char;
+        return c;
     }
 
     fn peek(&self) -> char {
-        return self.stream[*self.current.borrow()] as char;
+        return match self.decode(*self.current.borrow()) {
+            Some((c, _)) => c,
+            None => char::REPLACEMENT_CHARACTER,
+        };
     }
 
     fn peek_next(&self) -> char {
-        return self.stream[*self.current.borrow() + 1] as char;
+        let current = *self.current.borrow();
+        let next = current + self.decode(current).map_or(1, |(_, width)| width);
+        if next >= self.stream.len() {
+            return '\0';
+        }
+        return match self.decode(next) {
+            Some((c, _)) => c,
+            None => char::REPLACEMENT_CHARACTER,
+        };
     }
 
     fn skip_whitespace(&self) {
@@ -92,10 +135,33 @@ impl<'a> Scanner<'a> {
         return true;
     }
 
+    /// Scans string contents up to the closing quote or an interpolation.
+    /// Escapes are only skipped here; the parser decodes them.
     fn string(&self) -> Result<Token, QalamError> {
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line.replace_with(|&mut old| old + 1);
+            if self.decode(*self.current.borrow()).is_none() {
+                return Err(self.invalid_utf8());
+            }
+            match self.peek() {
+                '\n' => {
+                    self.line.replace_with(|&mut old| old + 1);
+                }
+                '\\' => {
+                    self.advance();
+                    if self.is_at_end() {
+                        break;
+                    }
+                    if self.peek() == '\n' {
+                        self.line.replace_with(|&mut old| old + 1);
+                    }
+                }
+                '$' if self.peek_next() == '{' => {
+                    self.advance();
+                    self.advance();
+                    self.interpolations.borrow_mut().push(0);
+                    return Ok(self.make_token(TokenType::INTERPOLATION));
+                }
+                _ => {}
             }
             self.advance();
         }
@@ -109,139 +175,128 @@ impl<'a> Scanner<'a> {
     }
 
     fn is_digit(&self, c: char) -> bool {
-        return c >= '0' && c <= '9';
+        return numerals::ascii_digit(c).is_some();
     }
 
     fn is_alpha(&self, c: char) -> bool {
-        return (c >= 'a' && c <= 'z') || (c >= 'A' && c <= 'Z') || c == '_';
+        return c == '_' || UnicodeXID::is_xid_start(c);
     }
 
-    fn check_keyword(&self, start: usize, rest: &str, token_type: TokenType) -> TokenType {
-        let diff = *self.current.borrow() - *self.start.borrow();
-        let length = rest.len();
-        if diff == start + length {
-            let moved_start = *self.start.borrow() + start;
-            let check_slice = &self.stream[moved_start..(moved_start + length)];
-            if check_slice == rest.as_bytes() {
-                return token_type;
-            }
+    fn identifier(&self) -> Token {
+        while UnicodeXID::is_xid_continue(self.peek()) {
+            self.advance();
         }
 
-        return TokenType::IDENTIFIER;
+        let text = std::str::from_utf8(&self.stream[*self.start.borrow()..*self.current.borrow()])
+            .unwrap_or("");
+        return self.make_token(self.keywords.token_type(text));
     }
 
-    fn identifier_type(&self) -> TokenType {
-        match self.stream[*self.start.borrow()] as char {
-            'a' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'w' => return TokenType::OR,
-                        'm' => return self.check_keyword(2, "al", TokenType::FUN),
-                        _ => {}
-                    }
-                }
-            }
-            'b' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'a' => {
-                            if *self.current.borrow() - *self.start.borrow() > 2 {
-                                match self.stream[*self.start.borrow() + 2] as char {
-                                    't' => return self.check_keyword(3, "il", TokenType::FALSE),
-                                    'y' => return self.check_keyword(3, "nama", TokenType::WHILE),
-                                    _ => {}
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            'g' => return self.check_keyword(1, "haib", TokenType::NIL),
-            'h' => return self.check_keyword(1, "aqq", TokenType::TRUE),
-            'i' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'f' => return self.check_keyword(2, "tar", TokenType::BREAK),
-                        'b' => return self.check_keyword(2, "n", TokenType::INHERITS),
-                        'l' => return self.check_keyword(2, "la", TokenType::ELSE),
-                        't' => return self.check_keyword(2, "ha", TokenType::IF),
-                        _ => {}
-                    }
-                }
-            }
-            'k' => return self.check_keyword(1, "itab", TokenType::CLASS),
-            'l' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'a' => {
-                            if *self.current.borrow() - *self.start.borrow() > 2 {
-                                match self.stream[*self.start.borrow() + 2] as char {
-                                    'z' => return self.check_keyword(3, "im", TokenType::CONST),
-                                    _ => {}
-                                }
-                            } else {
-                                return TokenType::BANG;
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            'n' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'a' => return self.check_keyword(2, "fs", TokenType::THIS),
-                        _ => {}
-                    }
-                }
-            }
-            'q' => {
-                return self.check_keyword(1, "ul", TokenType::PRINT);
-            }
-            'r' => {
-                return self.check_keyword(1, "add", TokenType::RETURN);
-            }
-            's' => {
-                if *self.current.borrow() - *self.start.borrow() > 1 {
-                    match self.stream[*self.start.borrow() + 1] as char {
-                        'h' => return self.check_keyword(2, "ai", TokenType::VAR),
-                        'a' => return self.check_keyword(2, "far", TokenType::CONTINUE),
-                        _ => {}
-                    }
-                }
-            }
-            't' => {
-                return self.check_keyword(1, "awaf", TokenType::FOR);
-            }
-            'u' => return self.check_keyword(1, "lya", TokenType::SUPER),
-            'w' => return self.check_keyword(1, "a", TokenType::AND),
-            _ => {}
+    fn is_radix_digit(&self, c: char, radix: u32) -> bool {
+        if radix == 10 {
+            return self.is_digit(c);
         }
-        return TokenType::IDENTIFIER;
+        return c.is_ascii() && c.is_digit(radix);
     }
 
-    fn identifier(&self) -> Token {
-        while self.is_alpha(self.peek()) || self.is_digit(self.peek()) {
+    /// Consumes the rest of a malformed number literal so the error shows
+    /// all of it.
+    fn number_error(&self, details: &str) -> QalamError {
+        while UnicodeXID::is_xid_continue(self.peek()) && !self.is_at_end() {
             self.advance();
         }
+        return QalamError::from_token_syntax(details, &self.make_token(TokenType::NUMBER));
+    }
 
-        return self.make_token(self.identifier_type());
+    /// Consumes a run of digits in `radix` with single `_` separators
+    /// between them, returning how many digits there were in total.
+    fn digits(&self, radix: u32, mut count: usize) -> Result<usize, QalamError> {
+        let mut underscore = false;
+        loop {
+            let c = self.peek();
+            if c == '_' {
+                if count == 0 || underscore {
+                    return Err(self.number_error("Misplaced '_' in number literal."));
+                }
+                underscore = true;
+            } else if self.is_radix_digit(c, radix) {
+                count += 1;
+                underscore = false;
+            } else {
+                break;
+            }
+            self.advance();
+        }
+        if underscore {
+            return Err(self.number_error("Misplaced '_' in number literal."));
+        }
+        return Ok(count);
     }
 
-    fn number(&self) -> Token {
-        while self.is_digit(self.peek()) {
+    fn number(&self, first: char) -> Result<Token, QalamError> {
+        let radix = match (first, self.peek()) {
+            ('0', 'x' | 'X') => 16,
+            ('0', 'b' | 'B') => 2,
+            ('0', 'o' | 'O') => 8,
+            _ => 10,
+        };
+        if radix != 10 {
+            self.advance();
+            if self.digits(radix, 0)? == 0 {
+                return Err(self.number_error("Expect digits after number prefix."));
+            }
+            return self.end_number();
+        }
+
+        self.digits(10, 1)?;
+        let separator = self.peek() == '.' || self.peek() == numerals::ARABIC_DECIMAL_SEPARATOR;
+        if separator && self.is_digit(self.peek_next()) {
             self.advance();
+            self.digits(10, 0)?;
         }
 
-        if self.peek() == '.' && self.is_digit(self.peek_next()) {
+        if self.peek() == 'e' || self.peek() == 'E' {
             self.advance();
-            while self.is_digit(self.peek()) {
+            if self.peek() == '+' || self.peek() == '-' {
                 self.advance();
             }
+            if self.digits(10, 0)? == 0 {
+                return Err(self.number_error("Expect digits in number exponent."));
+            }
         }
+        return self.end_number();
+    }
 
-        return self.make_token(TokenType::NUMBER);
+    fn end_number(&self) -> Result<Token, QalamError> {
+        if UnicodeXID::is_xid_continue(self.peek()) {
+            return Err(self.number_error("Invalid character in number literal."));
+        }
+        return Ok(self.make_token(TokenType::NUMBER));
+    }
+
+    /// Scans the next `n` token types without consuming them.
+    pub fn lookahead(&self, n: usize) -> Result<Vec<TokenType>, QalamError> {
+        let current = *self.current.borrow();
+        let start = *self.start.borrow();
+        let line = *self.line.borrow();
+        let interpolations = self.interpolations.borrow().clone();
+        let mut token_types = Vec::new();
+        let mut result = Ok(());
+        for _ in 0..n {
+            match self.scan() {
+                Ok(token) => token_types.push(token.token_type),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.current.replace(current);
+        self.start.replace(start);
+        self.line.replace(line);
+        self.interpolations.replace(interpolations);
+        result?;
+        return Ok(token_types);
     }
 
     pub fn scan(&self) -> Result<Token, QalamError> {
@@ -255,6 +310,9 @@ impl<'a> Scanner<'a> {
             ));
         }
 
+        if self.decode(*self.current.borrow()).is_none() {
+            return Err(self.invalid_utf8());
+        }
         let c = self.advance();
 
         if self.is_alpha(c) {
@@ -262,16 +320,58 @@ impl<'a> Scanner<'a> {
         }
 
         if self.is_digit(c) {
-            return Ok(self.number());
+            return self.number(c);
         }
         match c {
             '(' => return Ok(self.make_token(TokenType::LEFT_PAREN)),
             ')' => return Ok(self.make_token(TokenType::RIGHT_PAREN)),
-            '{' => return Ok(self.make_token(TokenType::LEFT_BRACE)),
-            '}' => return Ok(self.make_token(TokenType::RIGHT_BRACE)),
+            '{' => {
+                if let Some(depth) = self.interpolations.borrow_mut().last_mut() {
+                    *depth += 1;
+                }
+                return Ok(self.make_token(TokenType::LEFT_BRACE));
+            }
+            '}' => {
+                let mut interpolations = self.interpolations.borrow_mut();
+                match interpolations.last_mut() {
+                    Some(0) => {
+                        // closes the interpolation, the string continues
+                        interpolations.pop();
+                        drop(interpolations);
+                        return self.string();
+                    }
+                    Some(depth) => *depth -= 1,
+                    None => {}
+                }
+                return Ok(self.make_token(TokenType::RIGHT_BRACE));
+            }
+            '[' => return Ok(self.make_token(TokenType::LEFT_BRACKET)),
+            ']' => return Ok(self.make_token(TokenType::RIGHT_BRACKET)),
             ';' => return Ok(self.make_token(TokenType::SEMICOLON)),
             ',' => return Ok(self.make_token(TokenType::COMMA)),
-            '.' => return Ok(self.make_token(TokenType::DOT)),
+            ':' => return Ok(self.make_token(TokenType::COLON)),
+            '?' => {
+                if self.match_char('.') {
+                    return Ok(self.make_token(TokenType::QUESTION_DOT));
+                } else if self.match_char('?') {
+                    if self.match_char('=') {
+                        return Ok(self.make_token(TokenType::QUESTION_QUESTION_EQUAL));
+                    }
+                    return Ok(self.make_token(TokenType::QUESTION_QUESTION));
+                } else {
+                    return Ok(self.make_token(TokenType::QUESTION));
+                }
+            }
+            '.' => {
+                if self.match_char('.') {
+                    if self.match_char('=') {
+                        return Ok(self.make_token(TokenType::DOT_DOT_EQUAL));
+                    }
+                    return Ok(self.make_token(TokenType::DOT_DOT));
+                } else {
+                    return Ok(self.make_token(TokenType::DOT));
+                }
+            }
             '-' => {
                 if self.match_char('-') {
                     return Ok(self.make_token(TokenType::DECREMENT));
@@ -291,13 +391,32 @@ impl<'a> Scanner<'a> {
                 }
             }
             '*' => {
-                if self.match_char('=') {
+                if self.match_char('*') {
+                    if self.match_char('=') {
+                        return Ok(self.make_token(TokenType::STAR_STAR_EQUAL));
+                    }
+                    return Ok(self.make_token(TokenType::STAR_STAR));
+                } else if self.match_char('=') {
                     return Ok(self.make_token(TokenType::STAR_EQUAL));
                 } else {
                     return Ok(self.make_token(TokenType::STAR));
                 }
             }
             '%' => return Ok(self.make_token(TokenType::PERCENT)),
+            '~' => {
+                if self.match_char('/') {
+                    return Ok(self.make_token(TokenType::TILDE_SLASH));
+                } else {
+                    return Ok(self.make_token(TokenType::TILDE));
+                }
+            }
+            '^' => {
+                if self.match_char('=') {
+                    return Ok(self.make_token(TokenType::CARET_EQUAL));
+                } else {
+                    return Ok(self.make_token(TokenType::CARET));
+                }
+            }
             '/' => {
                 if self.match_char('=') {
                     return Ok(self.make_token(TokenType::SLASH_EQUAL));
@@ -308,11 +427,19 @@ impl<'a> Scanner<'a> {
             '&' => {
                 if self.match_char('&') {
                     return Ok(self.make_token(TokenType::AND));
+                } else if self.match_char('=') {
+                    return Ok(self.make_token(TokenType::AMPERSAND_EQUAL));
+                } else {
+                    return Ok(self.make_token(TokenType::AMPERSAND));
                 }
             }
             '|' => {
                 if self.match_char('|') {
                     return Ok(self.make_token(TokenType::OR));
+                } else if self.match_char('=') {
+                    return Ok(self.make_token(TokenType::PIPE_EQUAL));
+                } else {
+                    return Ok(self.make_token(TokenType::PIPE));
                 }
             }
             '!' => {
@@ -325,19 +452,31 @@ impl<'a> Scanner<'a> {
             '=' => {
                 if self.match_char('=') {
                     return Ok(self.make_token(TokenType::EQUAL_EQUAL));
+                } else if self.match_char('>') {
+                    return Ok(self.make_token(TokenType::FAT_ARROW));
                 } else {
                     return Ok(self.make_token(TokenType::EQUAL));
                 }
             }
             '<' => {
-                if self.match_char('=') {
+                if self.match_char('<') {
+                    if self.match_char('=') {
+                        return Ok(self.make_token(TokenType::LESS_LESS_EQUAL));
+                    }
+                    return Ok(self.make_token(TokenType::LESS_LESS));
+                } else if self.match_char('=') {
                     return Ok(self.make_token(TokenType::LESS_EQUAL));
                 } else {
                     return Ok(self.make_token(TokenType::LESS));
                 }
             }
             '>' => {
-                if self.match_char('=') {
+                if self.match_char('>') {
+                    if self.match_char('=') {
+                        return Ok(self.make_token(TokenType::GREATER_GREATER_EQUAL));
+                    }
+                    return Ok(self.make_token(TokenType::GREATER_GREATER));
+                } else if self.match_char('=') {
                     return Ok(self.make_token(TokenType::GREATER_EQUAL));
                 } else {
                     return Ok(self.make_token(TokenType::GREATER));
@@ -1,3 +1,5 @@
+use crate::error::QalamError;
+
 #[allow(non_camel_case_types)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum TokenType {
@@ -6,7 +8,11 @@ pub enum TokenType {
     RIGHT_PAREN,
     LEFT_BRACE,
     RIGHT_BRACE,
+    LEFT_BRACKET,
+    RIGHT_BRACKET,
     COMMA,
+    COLON,
+    QUESTION,
     DOT,
     MINUS,
     PLUS,
@@ -14,6 +20,10 @@ pub enum TokenType {
     SLASH,
     STAR,
     PERCENT,
+    AMPERSAND,
+    PIPE,
+    CARET,
+    TILDE,
 
     // One or two character tokens.
     BANG,
@@ -24,9 +34,25 @@ pub enum TokenType {
     GREATER_EQUAL,
     LESS,
     LESS_EQUAL,
+    LESS_LESS,
+    LESS_LESS_EQUAL,
+    GREATER_GREATER,
+    GREATER_GREATER_EQUAL,
+    AMPERSAND_EQUAL,
+    PIPE_EQUAL,
+    CARET_EQUAL,
+    TILDE_SLASH,
+    STAR_STAR,
+    QUESTION_DOT,
+    QUESTION_QUESTION,
+    QUESTION_QUESTION_EQUAL,
+    DOT_DOT,
+    DOT_DOT_EQUAL,
+    FAT_ARROW,
     PLUS_EQUAL,
     MINUS_EQUAL,
     STAR_EQUAL,
+    STAR_STAR_EQUAL,
     SLASH_EQUAL,
     INCREMENT,
     DECREMENT,
@@ -34,6 +60,7 @@ pub enum TokenType {
     // Literals.
     IDENTIFIER,
     STRING,
+    INTERPOLATION,
     NUMBER,
 
     // Keywords.
@@ -57,6 +84,14 @@ pub enum TokenType {
     BREAK,
     CONTINUE,
     INHERITS,
+    MATCH,
+    THROW,
+    TRY,
+    CATCH,
+    FINALLY,
+    IMPORT,
+    AS,
+    IN,
 
     EOF,
 }
@@ -76,4 +111,10 @@ impl<'a> Token<'a> {
             line,
         }
     }
+
+    /// The token's source text, which the scanner guarantees is UTF-8.
+    pub fn lexeme(&self) -> Result<&'a str, QalamError> {
+        return std::str::from_utf8(self.literal)
+            .map_err(|_| QalamError::with_line_syntax("Invalid UTF-8 sequence.", self.line));
+    }
 }
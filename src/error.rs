@@ -1,12 +1,15 @@
 use std::fmt;
 
 use crate::compiler::token::Token;
+use crate::value::Value;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ErrorType {
     Syntax,
     Compile,
     Runtime,
+    /// Reported without stopping compilation.
+    Warning,
 }
 
 impl fmt::Display for ErrorType {
@@ -15,6 +18,7 @@ impl fmt::Display for ErrorType {
             Self::Compile => "CompileError",
             Self::Runtime => "RuntimeError",
             Self::Syntax => "SyntaxError",
+            Self::Warning => "Warning",
         };
         write!(f, "{}", str)
     }
@@ -24,6 +28,11 @@ impl fmt::Display for ErrorType {
 pub struct QalamError {
     details: String,
     err_type: ErrorType,
+    line: Option<usize>,
+    /// Source text of the token the error points at.
+    lexeme: Option<String>,
+    /// The value passed to `irmi`, handed to `imsak` unchanged.
+    thrown: Option<Value>,
 }
 
 impl QalamError {
@@ -31,6 +40,9 @@ impl QalamError {
         Self {
             details: details.to_string(),
             err_type,
+            line: None,
+            lexeme: None,
+            thrown: None,
         }
     }
 
@@ -46,47 +58,96 @@ impl QalamError {
         Self::new(details, ErrorType::Syntax)
     }
 
-    fn token_message(details: &str, token: &Token) -> String {
-        return format!(
-            "{}\n\tat line {}\n\tat '{}'",
-            details,
-            token.line,
-            std::str::from_utf8(token.literal).unwrap()
-        );
+    fn from_token(details: &str, token: &Token, err_type: ErrorType) -> Self {
+        let mut error = Self::with_line(details, token.line, err_type);
+        error.lexeme = Some(String::from_utf8_lossy(token.literal).to_string());
+        return error;
     }
 
     pub fn from_token_syntax(details: &str, token: &Token) -> Self {
-        Self::new(&Self::token_message(details, token), ErrorType::Syntax)
+        Self::from_token(details, token, ErrorType::Syntax)
     }
 
     pub fn from_token_compile(details: &str, token: &Token) -> Self {
-        Self::new(&Self::token_message(details, token), ErrorType::Compile)
+        Self::from_token(details, token, ErrorType::Compile)
     }
 
     pub fn from_token_runtime(details: &str, token: &Token) -> Self {
-        Self::new(&Self::token_message(details, token), ErrorType::Runtime)
+        Self::from_token(details, token, ErrorType::Runtime)
+    }
+
+    pub fn from_token_warning(details: &str, token: &Token) -> Self {
+        Self::from_token(details, token, ErrorType::Warning)
     }
 
-    fn message_with_line(details: &str, line: usize) -> String {
-        return format!("{}\n\tat line {}", details, line);
+    fn with_line(details: &str, line: usize, err_type: ErrorType) -> Self {
+        let mut error = Self::new(details, err_type);
+        error.line = Some(line);
+        return error;
     }
 
     pub fn with_line_syntax(details: &str, line: usize) -> Self {
-        Self::new(&Self::message_with_line(details, line), ErrorType::Syntax)
+        Self::with_line(details, line, ErrorType::Syntax)
     }
 
     pub fn with_line_compile(details: &str, line: usize) -> Self {
-        Self::new(&Self::message_with_line(details, line), ErrorType::Compile)
+        Self::with_line(details, line, ErrorType::Compile)
     }
 
     pub fn with_line_runtime(details: &str, line: usize) -> Self {
-        Self::new(&Self::message_with_line(details, line), ErrorType::Runtime)
+        Self::with_line(details, line, ErrorType::Runtime)
+    }
+
+    /// An error raised by `irmi`. A caught error is raised again with the type, message and line it was caught with.
+    pub fn thrown(value: Value, line: usize) -> Self {
+        let mut error = match &value {
+            Value::Error(caught) => Self::new(&caught.message, caught.err_type.clone()),
+            _ => Self::new(&format!("Uncaught {}.", value.repr()), ErrorType::Runtime),
+        };
+        error.line = match &value {
+            Value::Error(caught) => caught.line.or(Some(line)),
+            _ => Some(line),
+        };
+        error.thrown = Some(value);
+        return error;
+    }
+
+    /// Attaches the line an error surfaced at, e.g. the call site of a native function.
+    pub fn at_line(mut self, line: usize) -> Self {
+        if self.line.is_none() {
+            self.line = Some(line);
+        }
+        return self;
+    }
+
+    pub fn err_type(&self) -> &ErrorType {
+        return &self.err_type;
+    }
+
+    pub fn details(&self) -> &str {
+        return &self.details;
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        return self.line;
+    }
+
+    /// The value a script handler receives for this error.
+    pub fn thrown_value(&self) -> Option<Value> {
+        return self.thrown.clone();
     }
 }
 
 impl fmt::Display for QalamError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.err_type, self.details)
+        write!(f, "{}: {}", self.err_type, self.details)?;
+        if let Some(line) = self.line {
+            write!(f, "\n\tat line {}", line)?;
+        }
+        if let Some(lexeme) = &self.lexeme {
+            write!(f, "\n\tat '{}'", lexeme)?;
+        }
+        return Ok(());
     }
 }
 
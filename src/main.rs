@@ -3,6 +3,13 @@
 use rqalam::{error::QalamError, vm::VM};
 
 use std::io::Write;
+use std::path::Path;
+
+fn print_warnings(vm: &VM) {
+    for warning in vm.warnings() {
+        eprintln!("{}", warning);
+    }
+}
 
 pub fn repl() -> Result<(), QalamError> {
     loop {
@@ -16,7 +23,9 @@ pub fn repl() -> Result<(), QalamError> {
                 }
                 let stream = Vec::<u8>::from(input.clone());
                 let mut vm = VM::new();
-                vm.interpret(stream)?;
+                let result = vm.interpret(stream);
+                print_warnings(&vm);
+                result?;
                 input.clear();
             }
             Err(e) => {
@@ -29,12 +38,19 @@ pub fn repl() -> Result<(), QalamError> {
 }
 
 pub fn source(path: &String) -> Result<(), QalamError> {
-    match std::fs::read_to_string(path) {
-        Ok(contents) => {
-            let stream = Vec::<u8>::from(contents.clone() + "\n");
+    match std::fs::read(path) {
+        Ok(mut stream) => {
+            stream.push(b'\n');
             let mut vm = VM::new();
-            vm.interpret(stream)?;
-            return Ok(());
+            vm.set_script_path(Path::new(path));
+            if let Some(paths) = std::env::var_os("QALAM_PATH") {
+                for path in std::env::split_paths(&paths) {
+                    vm.add_search_path(&path);
+                }
+            }
+            let result = vm.interpret(stream);
+            print_warnings(&vm);
+            return result;
         }
         Err(e) => return Err(QalamError::new_compile(&format!("{}", e))),
     }
@@ -0,0 +1,429 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+const LIMB_BITS: u32 = 32;
+/// The largest power of ten that fits in a limb, used to print 9 digits at a time.
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+
+/// An arbitrary-precision integer.
+///
+/// The magnitude is stored in base 2^32, least significant limb first, and is always trimmed of leading zero limbs
+/// so that derived equality and hashing compare values rather than representations. Zero is never negative.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+    for (a, b) in a.iter().rev().zip(b.iter().rev()) {
+        if a != b {
+            return a.cmp(b);
+        }
+    }
+    return Ordering::Equal;
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u64;
+    for i in 0..a.len().max(b.len()) {
+        let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> LIMB_BITS;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    return result;
+}
+
+/// Subtracts `b` from `a`, which must be at least as large.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for i in 0..a.len() {
+        let mut diff = a[i] as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if diff < 0 {
+            diff += 1 << LIMB_BITS;
+            borrow = 1;
+        }
+        result.push(diff as u32);
+    }
+    trim(&mut result);
+    return result;
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, a) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, b) in b.iter().enumerate() {
+            let product = *a as u64 * *b as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> LIMB_BITS;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    trim(&mut result);
+    return result;
+}
+
+fn divmod_small(a: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder = 0u64;
+    for i in (0..a.len()).rev() {
+        let current = (remainder << LIMB_BITS) | a[i] as u64;
+        quotient[i] = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    trim(&mut quotient);
+    return (quotient, remainder as u32);
+}
+
+/// Binary long division of magnitudes; `b` must be non-zero.
+fn divmod_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if b.len() == 1 {
+        let (quotient, remainder) = divmod_small(a, b[0]);
+        let mut remainder = vec![remainder];
+        trim(&mut remainder);
+        return (quotient, remainder);
+    }
+    let mut quotient = vec![0u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..a.len() * LIMB_BITS as usize).rev() {
+        let limb = bit / LIMB_BITS as usize;
+        let shift = bit % LIMB_BITS as usize;
+        // remainder = remainder * 2 + the next bit of a
+        let mut carry = (a[limb] >> shift) & 1;
+        for r in remainder.iter_mut() {
+            let next = *r >> (LIMB_BITS - 1);
+            *r = (*r << 1) | carry;
+            carry = next;
+        }
+        if carry > 0 {
+            remainder.push(carry);
+        }
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            quotient[limb] |= 1 << shift;
+        }
+    }
+    trim(&mut quotient);
+    return (quotient, remainder);
+}
+
+impl BigInt {
+    fn new(negative: bool, mut limbs: Vec<u32>) -> Self {
+        trim(&mut limbs);
+        let negative = negative && !limbs.is_empty();
+        return Self { negative, limbs };
+    }
+
+    pub fn from_i64(num: i64) -> Self {
+        let magnitude = num.unsigned_abs();
+        return Self::new(
+            num < 0,
+            vec![magnitude as u32, (magnitude >> LIMB_BITS) as u32],
+        );
+    }
+
+    /// The integer equal to `num`, if `num` is integral.
+    pub fn from_f64(num: f64) -> Option<Self> {
+        if !num.is_finite() || num.fract() != 0.0 {
+            return None;
+        }
+        let base = (1u64 << LIMB_BITS) as f64;
+        let mut magnitude = num.abs();
+        let mut limbs = Vec::new();
+        // exact, since integral floats only lose low bits, never gain fractions
+        while magnitude >= 1.0 {
+            limbs.push((magnitude % base) as u32);
+            magnitude = (magnitude / base).floor();
+        }
+        return Some(Self::new(num < 0.0, limbs));
+    }
+
+    /// Parses unsigned digits in `radix`, without prefix or separators.
+    pub fn parse(digits: &str, radix: u32) -> Option<Self> {
+        let mut limbs: Vec<u32> = Vec::new();
+        for c in digits.chars() {
+            let mut carry = c.to_digit(radix)? as u64;
+            for limb in limbs.iter_mut() {
+                let current = *limb as u64 * radix as u64 + carry;
+                *limb = current as u32;
+                carry = current >> LIMB_BITS;
+            }
+            if carry > 0 {
+                limbs.push(carry as u32);
+            }
+        }
+        return Some(Self::new(false, limbs));
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+        let mut magnitude = 0u64;
+        for limb in self.limbs.iter().rev() {
+            magnitude = (magnitude << LIMB_BITS) | *limb as u64;
+        }
+        if self.negative {
+            return (-(magnitude as i128)).try_into().ok();
+        }
+        return magnitude.try_into().ok();
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let base = (1u64 << LIMB_BITS) as f64;
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0.0, |acc, limb| acc * base + *limb as f64);
+        return if self.negative { -magnitude } else { magnitude };
+    }
+
+    pub fn is_zero(&self) -> bool {
+        return self.limbs.is_empty();
+    }
+
+    /// The number of bits in the magnitude, 0 for zero.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(top) => {
+                let below = (self.limbs.len() as u64 - 1) * LIMB_BITS as u64;
+                return below + (LIMB_BITS - top.leading_zeros()) as u64;
+            }
+            None => return 0,
+        }
+    }
+
+    pub fn neg(&self) -> Self {
+        return Self::new(!self.negative, self.limbs.clone());
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return Self::new(self.negative, add_magnitude(&self.limbs, &other.limbs));
+        }
+        return match cmp_magnitude(&self.limbs, &other.limbs) {
+            Ordering::Less => Self::new(other.negative, sub_magnitude(&other.limbs, &self.limbs)),
+            _ => Self::new(self.negative, sub_magnitude(&self.limbs, &other.limbs)),
+        };
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        return self.add(&other.neg());
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        return Self::new(
+            self.negative != other.negative,
+            mul_magnitude(&self.limbs, &other.limbs),
+        );
+    }
+
+    /// Exponentiation by squaring.
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut result = Self::from_i64(1);
+        let mut base = self.clone();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base);
+            }
+        }
+        return result;
+    }
+
+    /// The value in two's complement over `len` limbs, which must leave room for the sign bit.
+    fn to_twos_complement(&self, len: usize) -> Vec<u32> {
+        if !self.negative {
+            let mut limbs = self.limbs.clone();
+            limbs.resize(len, 0);
+            return limbs;
+        }
+        // -x == !(x - 1)
+        let mut limbs = sub_magnitude(&self.limbs, &[1]);
+        limbs.resize(len, 0);
+        return limbs.iter().map(|limb| !limb).collect();
+    }
+
+    fn from_twos_complement(limbs: Vec<u32>) -> Self {
+        if limbs
+            .last()
+            .is_some_and(|limb| limb >> (LIMB_BITS - 1) == 1)
+        {
+            let inverted = limbs.iter().map(|limb| !limb).collect::<Vec<u32>>();
+            return Self::new(true, add_magnitude(&inverted, &[1]));
+        }
+        return Self::new(false, limbs);
+    }
+
+    /// Applies `op` limb by limb to the two's complement forms, as if both were sign-extended forever.
+    fn bitwise(&self, other: &Self, op: fn(u32, u32) -> u32) -> Self {
+        let len = self.limbs.len().max(other.limbs.len()) + 1;
+        let a = self.to_twos_complement(len);
+        let b = other.to_twos_complement(len);
+        return Self::from_twos_complement(
+            a.iter().zip(b.iter()).map(|(a, b)| op(*a, *b)).collect(),
+        );
+    }
+
+    pub fn bitand(&self, other: &Self) -> Self {
+        return self.bitwise(other, |a, b| a & b);
+    }
+
+    pub fn bitor(&self, other: &Self) -> Self {
+        return self.bitwise(other, |a, b| a | b);
+    }
+
+    pub fn bitxor(&self, other: &Self) -> Self {
+        return self.bitwise(other, |a, b| a ^ b);
+    }
+
+    pub fn not(&self) -> Self {
+        return self.neg().sub(&Self::from_i64(1));
+    }
+
+    pub fn shl(&self, shift: u32) -> Self {
+        let bit_shift = shift % LIMB_BITS;
+        let mut limbs = vec![0u32; (shift / LIMB_BITS) as usize];
+        let mut carry = 0u32;
+        for limb in self.limbs.iter() {
+            if bit_shift == 0 {
+                limbs.push(*limb);
+                continue;
+            }
+            limbs.push((limb << bit_shift) | carry);
+            carry = limb >> (LIMB_BITS - bit_shift);
+        }
+        limbs.push(carry);
+        return Self::new(self.negative, limbs);
+    }
+
+    /// Arithmetic shift, rounding towards negative infinity like `i64`'s `>>`.
+    pub fn shr(&self, shift: u32) -> Self {
+        if self.negative {
+            // -x >> n == -((x - 1) >> n) - 1
+            let magnitude = Self::new(false, sub_magnitude(&self.limbs, &[1]));
+            return magnitude.shr(shift).not();
+        }
+        let limb_shift = (shift / LIMB_BITS) as usize;
+        let bit_shift = shift % LIMB_BITS;
+        let mut limbs = Vec::new();
+        for i in limb_shift..self.limbs.len() {
+            let mut limb = self.limbs[i] >> bit_shift;
+            if bit_shift > 0 {
+                limb |= self
+                    .limbs
+                    .get(i + 1)
+                    .map_or(0, |next| next << (LIMB_BITS - bit_shift));
+            }
+            limbs.push(limb);
+        }
+        return Self::new(false, limbs);
+    }
+
+    /// Truncating division, so the remainder takes the sign of `self` like `i64`'s `/` and `%`. `None` when dividing
+    /// by zero.
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, remainder) = divmod_magnitude(&self.limbs, &other.limbs);
+        return Some((
+            Self::new(self.negative != other.negative, quotient),
+            Self::new(self.negative, remainder),
+        ));
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        };
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut chunks = Vec::new();
+        let mut magnitude = self.limbs.clone();
+        while !magnitude.is_empty() {
+            let (quotient, chunk) = divmod_small(&magnitude, DECIMAL_CHUNK);
+            chunks.push(chunk);
+            magnitude = quotient;
+        }
+        let mut text = String::from(if self.negative { "-" } else { "" });
+        text += &chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            text += &format!("{:09}", chunk);
+        }
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BigInt;
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigInt::from_i64(i64::MAX);
+        let big = max.mul(&max);
+        assert_eq!(big.to_string(), "85070591730234615847396907784232501249");
+        assert_eq!(
+            BigInt::parse("85070591730234615847396907784232501249", 10),
+            Some(big.clone())
+        );
+        let (quotient, remainder) = big.add(&BigInt::from_i64(5)).div_rem(&max).unwrap();
+        assert_eq!(quotient, max);
+        assert_eq!(remainder.to_i64(), Some(5));
+        let min = BigInt::from_i64(i64::MIN);
+        assert_eq!(min.to_i64(), Some(i64::MIN));
+        assert_eq!(min.neg().to_string(), "9223372036854775808");
+        assert_eq!(min.neg().to_i64(), None);
+        assert!(min < max.neg());
+        assert_eq!(
+            BigInt::from_f64(1e20).unwrap().to_string(),
+            "100000000000000000000"
+        );
+        let big = BigInt::from_i64(-5).shl(100);
+        assert_eq!(big.shr(100).to_i64(), Some(-5));
+        assert_eq!(big.shr(200).to_i64(), Some(-1));
+        assert_eq!(big.bitand(&BigInt::from_i64(-1)), big);
+        assert_eq!(big.bitxor(&big).to_i64(), Some(0));
+        assert_eq!(big.not().bitor(&big).to_i64(), Some(-1));
+        let (_, remainder) = BigInt::from_i64(-7).div_rem(&BigInt::from_i64(2)).unwrap();
+        assert_eq!(remainder.to_i64(), Some(-1));
+    }
+}
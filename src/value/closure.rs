@@ -0,0 +1,39 @@
+use super::function::Function;
+use super::Value;
+use crate::vm::table::Table;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// A captured variable. Open while the variable still lives on the stack, closed once its slot is discarded.
+pub enum Upvalue {
+    Open(usize),
+    Closed(Value),
+}
+
+pub struct Closure {
+    pub function: Rc<Function>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    /// Globals of the module the closure was created in.
+    pub globals: Rc<RefCell<Table>>,
+}
+
+impl Closure {
+    pub fn new(
+        function: Rc<Function>,
+        upvalues: Vec<Rc<RefCell<Upvalue>>>,
+        globals: Rc<RefCell<Table>>,
+    ) -> Self {
+        Self {
+            function,
+            upvalues,
+            globals,
+        }
+    }
+}
+
+impl Display for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.function)
+    }
+}
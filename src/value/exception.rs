@@ -0,0 +1,41 @@
+use super::Value;
+use crate::error::{ErrorType, QalamError};
+use std::fmt::Display;
+
+/// An error raised by the VM, as seen by an `imsak` block.
+pub struct Exception {
+    pub err_type: ErrorType,
+    pub message: String,
+    pub line: Option<usize>,
+}
+
+impl Exception {
+    pub fn new(error: &QalamError) -> Self {
+        Self {
+            err_type: error.err_type().clone(),
+            message: error.details().to_string(),
+            line: error.line(),
+        }
+    }
+
+    /// Reads the `type`, `message` and `line` properties.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match name {
+            "type" => return Some(Value::String(self.err_type.to_string())),
+            "message" => return Some(Value::String(self.message.clone())),
+            "line" => {
+                return Some(
+                    self.line
+                        .map_or(Value::Null, |line| Value::Int(line as i64)),
+                )
+            }
+            _ => return None,
+        }
+    }
+}
+
+impl Display for Exception {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.err_type, self.message)
+    }
+}
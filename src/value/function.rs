@@ -0,0 +1,31 @@
+use crate::chunk::Chunk;
+use crate::error::QalamError;
+use std::fmt::Display;
+
+pub struct Function {
+    pub name: String,
+    pub arity: usize,
+    pub chunk: Chunk,
+    /// Warnings from compiling the file, only kept on its script function.
+    pub warnings: Vec<QalamError>,
+}
+
+impl Function {
+    pub fn new(name: String, arity: usize, chunk: Chunk) -> Self {
+        Self {
+            name,
+            arity,
+            chunk,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.name == "__main__" {
+            return write!(f, "<script>");
+        }
+        write!(f, "<amal {}>", self.name)
+    }
+}
@@ -0,0 +1,49 @@
+use super::map::Map;
+use super::Value;
+use crate::error::QalamError;
+
+/// The iteration protocol behind `tawaf (shai x fi ...)`. A loop keeps a
+/// cursor, starting at 0, and asks for the item at it along with the cursor
+/// of the item after.
+pub trait Iterable {
+    fn next_item(&self, cursor: usize) -> Option<(Value, usize)>;
+}
+
+impl Iterable for Vec<Value> {
+    fn next_item(&self, cursor: usize) -> Option<(Value, usize)> {
+        let item = self.get(cursor)?;
+        return Some((item.clone(), cursor + 1));
+    }
+}
+
+/// Strings yield their characters; the cursor is a byte offset.
+impl Iterable for String {
+    fn next_item(&self, cursor: usize) -> Option<(Value, usize)> {
+        let c = self[cursor..].chars().next()?;
+        return Some((Value::String(c.to_string()), cursor + c.len_utf8()));
+    }
+}
+
+/// Maps yield their keys in insertion order.
+impl Iterable for Map {
+    fn next_item(&self, cursor: usize) -> Option<(Value, usize)> {
+        let (key, _) = self.entries().get(cursor)?;
+        return Some((key.to_value(), cursor + 1));
+    }
+}
+
+impl Value {
+    /// Steps the iteration over this value, or fails if it can't be iterated.
+    pub fn next_item(&self, cursor: usize) -> Result<Option<(Value, usize)>, QalamError> {
+        match self {
+            Value::List(list) => return Ok(list.borrow().next_item(cursor)),
+            Value::String(string) => return Ok(string.next_item(cursor)),
+            Value::Map(map) => return Ok(map.borrow().next_item(cursor)),
+            _ => {
+                return Err(QalamError::new_runtime(
+                    "Can only iterate over lists, strings, maps and ranges.",
+                ))
+            }
+        }
+    }
+}
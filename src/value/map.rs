@@ -0,0 +1,127 @@
+use super::bigint::BigInt;
+use super::Value;
+use crate::error::QalamError;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The hashable form of a map key.
+///
+/// Integral numbers are keyed as integers, so `1` and `1.0` are the same key. Other numbers are keyed by their bit
+/// pattern after folding `-0.0` into `0.0`, so keys that compare equal with `==` also hash equal. `NaN` never equals itself, so it is rejected as a key rather than creating entries that can't be
+/// looked up again.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    String(String),
+    Number(u64),
+    Int(i64),
+    BigInt(BigInt),
+    Bool(bool),
+}
+
+impl MapKey {
+    pub fn from_value(value: &Value) -> Result<Self, QalamError> {
+        match value {
+            Value::String(string) => return Ok(Self::String(string.clone())),
+            Value::Bool(bool) => return Ok(Self::Bool(*bool)),
+            Value::Int(num) => return Ok(Self::Int(*num)),
+            Value::BigInt(num) => return Ok(Self::BigInt(num.as_ref().clone())),
+            Value::Number(num) => {
+                if let Some(num) = super::float_to_int(*num) {
+                    return Ok(Self::Int(num));
+                }
+                if let Some(num) = BigInt::from_f64(*num) {
+                    return Ok(Self::BigInt(num));
+                }
+                if num.is_nan() {
+                    return Err(QalamError::new_runtime("Map key cannot be NaN."));
+                }
+                let num = if *num == 0.0 { 0.0 } else { *num };
+                return Ok(Self::Number(num.to_bits()));
+            }
+            _ => {
+                return Err(QalamError::new_runtime(
+                    "Map keys must be strings, numbers or booleans.",
+                ))
+            }
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            Self::String(string) => Value::String(string.clone()),
+            Self::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            Self::Int(num) => Value::Int(*num),
+            Self::BigInt(num) => Value::BigInt(Rc::new(num.clone())),
+            Self::Bool(bool) => Value::Bool(*bool),
+        }
+    }
+}
+
+/// A hash map that remembers insertion order, so printing and iteration are deterministic.
+pub struct Map {
+    entries: Vec<(MapKey, Value)>,
+    indices: HashMap<MapKey, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            indices: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<Value> {
+        let idx = self.indices.get(key)?;
+        return Some(self.entries[*idx].1.clone());
+    }
+
+    pub fn insert(&mut self, key: MapKey, val: Value) {
+        if let Some(idx) = self.indices.get(&key) {
+            self.entries[*idx].1 = val;
+            return;
+        }
+        self.indices.insert(key.clone(), self.entries.len());
+        self.entries.push((key, val));
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<Value> {
+        let idx = self.indices.remove(key)?;
+        let (_, val) = self.entries.remove(idx);
+        // entries after the removed one shifted down by one
+        for (key, _) in self.entries[idx..].iter() {
+            *self.indices.get_mut(key).unwrap() -= 1;
+        }
+        return Some(val);
+    }
+
+    pub fn entries(&self) -> &Vec<(MapKey, Value)> {
+        return &self.entries;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::map::{Map, MapKey};
+    use crate::value::Value;
+
+    #[test]
+    fn test_number_keys() {
+        let zero = MapKey::from_value(&Value::Number(0.0)).unwrap();
+        let negative_zero = MapKey::from_value(&Value::Number(-0.0)).unwrap();
+        assert_eq!(zero, negative_zero);
+        assert!(MapKey::from_value(&Value::Number(f64::NAN)).is_err());
+
+        let mut map = Map::new();
+        map.insert(zero, Value::Bool(true));
+        map.insert(MapKey::String(String::from("a")), Value::Null);
+        assert!(map.get(&negative_zero) == Some(Value::Bool(true)));
+        assert!(map.remove(&negative_zero).is_some());
+        assert!(map.get(&MapKey::String(String::from("a"))) == Some(Value::Null));
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -1,26 +1,134 @@
+use bigint::BigInt;
+use class::{BoundMethod, Class, Instance};
+use closure::Closure;
+use exception::Exception;
+use map::Map;
+use module::Module;
+use native::Native;
+use std::cell::RefCell;
 use std::fmt::Display;
+use std::rc::Rc;
+pub mod bigint;
+pub mod class;
+pub mod closure;
+pub mod exception;
+pub mod function;
+pub mod iterable;
+pub mod map;
+pub mod module;
+pub mod native;
+pub mod numerals;
 
 #[derive(Clone)]
 pub enum Value {
     Number(f64),
+    Int(i64),
+    /// Only holds integers outside the range of `Int`, see `Value::from_big`.
+    BigInt(Rc<BigInt>),
     Bool(bool),
     Null,
     String(String),
+    Closure(Rc<Closure>),
+    Class(Rc<RefCell<Class>>),
+    Instance(Rc<RefCell<Instance>>),
+    BoundMethod(Rc<BoundMethod>),
+    Native(Rc<Native>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    Error(Rc<Exception>),
+    Module(Rc<Module>),
 }
 
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
+        return self.equals(other, &mut Vec::new());
+    }
+}
+
+/// Containers on the path from the outermost value, so self-referencing lists and maps don't recurse forever.
+type Visited = Vec<(*const (), *const ())>;
+
+impl Value {
+    fn equals(&self, other: &Self, visited: &mut Visited) -> bool {
         match (self, other) {
             (Self::Number(a), Self::Number(b)) => a == b,
+            (Self::Int(a), Self::Int(b)) => a == b,
+            (Self::Int(a), Self::Number(b)) | (Self::Number(b), Self::Int(a)) => {
+                float_to_int(*b) == Some(*a)
+            }
+            (Self::BigInt(a), Self::BigInt(b)) => a == b,
+            (Self::BigInt(a), Self::Number(b)) | (Self::Number(b), Self::BigInt(a)) => {
+                BigInt::from_f64(*b).as_ref() == Some(a.as_ref())
+            }
             (Self::Bool(a), Self::Bool(b)) => a == b,
             (Self::Null, Self::Null) => true,
             (Self::String(a), Self::String(b)) => a == b,
+            (Self::Closure(a), Self::Closure(b)) => Rc::ptr_eq(a, b),
+            (Self::Class(a), Self::Class(b)) => Rc::ptr_eq(a, b),
+            (Self::Instance(a), Self::Instance(b)) => Rc::ptr_eq(a, b),
+            (Self::BoundMethod(a), Self::BoundMethod(b)) => Rc::ptr_eq(a, b),
+            (Self::Native(a), Self::Native(b)) => Rc::ptr_eq(a, b),
+            (Self::Error(a), Self::Error(b)) => Rc::ptr_eq(a, b),
+            (Self::Module(a), Self::Module(b)) => Rc::ptr_eq(a, b),
+            (Self::List(a), Self::List(b)) => {
+                return containers_equal(a, b, visited, |a, b, visited| {
+                    return a.len() == b.len()
+                        && a.iter().zip(b.iter()).all(|(a, b)| a.equals(b, visited));
+                });
+            }
+            (Self::Map(a), Self::Map(b)) => {
+                return containers_equal(a, b, visited, |a, b, visited| {
+                    return a.len() == b.len()
+                        && a.entries().iter().all(|(key, val)| match b.get(key) {
+                            Some(other) => val.equals(&other, visited),
+                            None => false,
+                        });
+                });
+            }
             _ => false,
         }
     }
 }
 
+/// Compares the contents of two lists or maps unless `a` and `b` are the same container or already being compared.
+fn containers_equal<T>(
+    a: &Rc<RefCell<T>>,
+    b: &Rc<RefCell<T>>,
+    visited: &mut Visited,
+    contents_equal: impl FnOnce(&T, &T, &mut Visited) -> bool,
+) -> bool {
+    if Rc::ptr_eq(a, b) {
+        return true;
+    }
+    let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+    // a pair already being compared can only differ somewhere else
+    if visited.contains(&pair) {
+        return true;
+    }
+    visited.push(pair);
+    let equal = contents_equal(&a.borrow(), &b.borrow(), visited);
+    visited.pop();
+    return equal;
+}
+
+/// The integer equal to `num`, if there is one.
+pub fn float_to_int(num: f64) -> Option<i64> {
+    // i64::MAX isn't representable as f64, so the upper bound is exclusive
+    if num.fract() == 0.0 && num >= i64::MIN as f64 && num < i64::MAX as f64 {
+        return Some(num as i64);
+    }
+    return None;
+}
+
 impl Value {
+    /// Wraps an integer result, narrowing it to `Int` when it fits.
+    pub fn from_big(num: BigInt) -> Self {
+        match num.to_i64() {
+            Some(num) => return Value::Int(num),
+            None => return Value::BigInt(Rc::new(num)),
+        }
+    }
+
     pub fn is_falsy(&self) -> bool {
         if let Value::Bool(bool) = self {
             return !bool;
@@ -30,15 +138,116 @@ impl Value {
             return false;
         }
     }
+
+    /// Formats the value as it appears nested inside a list or map, quoting strings so `["1"]` and `[1]` differ.
+    pub fn repr(&self) -> String {
+        return self.nested(&mut Vec::new());
+    }
+
+    /// `repr` for a value inside the containers in `visited`; a container that holds itself prints as `[...]` or
+    /// `{...}`.
+    fn nested(&self, visited: &mut Vec<*const ()>) -> String {
+        match self {
+            Self::String(string) => return format!("\"{}\"", string),
+            Self::List(list) => {
+                let ptr = Rc::as_ptr(list) as *const ();
+                if visited.contains(&ptr) {
+                    return String::from("[...]");
+                }
+                visited.push(ptr);
+                let items = list
+                    .borrow()
+                    .iter()
+                    .map(|item| item.nested(visited))
+                    .collect::<Vec<String>>();
+                visited.pop();
+                return format!("[{}]", items.join(", "));
+            }
+            Self::Map(map) => {
+                let ptr = Rc::as_ptr(map) as *const ();
+                if visited.contains(&ptr) {
+                    return String::from("{...}");
+                }
+                visited.push(ptr);
+                let entries = map
+                    .borrow()
+                    .entries()
+                    .iter()
+                    .map(|(key, val)| format!("{}: {}", key.to_value().repr(), val.nested(visited)))
+                    .collect::<Vec<String>>();
+                visited.pop();
+                return format!("{{{}}}", entries.join(", "));
+            }
+            _ => return format!("{}", self),
+        }
+    }
 }
 
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Number(num) => write!(f, "{}", num),
+            Self::Number(num) => write!(f, "{}", numerals::localize(num.to_string())),
+            Self::Int(num) => write!(f, "{}", numerals::localize(num.to_string())),
+            Self::BigInt(num) => write!(f, "{}", numerals::localize(num.to_string())),
             Self::Bool(bool) => write!(f, "{}", if *bool { "haqq" } else { "batil" }),
             Self::Null => write!(f, "ghaib"),
             Self::String(string) => write!(f, "{}", string),
+            Self::Closure(closure) => write!(f, "{}", closure),
+            Self::Class(class) => write!(f, "{}", class.borrow()),
+            Self::Instance(instance) => write!(f, "{}", instance.borrow()),
+            Self::BoundMethod(method) => write!(f, "{}", method),
+            Self::Native(native) => write!(f, "{}", native),
+            Self::Error(error) => write!(f, "{}", error),
+            Self::Module(module) => write!(f, "{}", module),
+            Self::List(_) | Self::Map(_) => write!(f, "{}", self.repr()),
         }
     }
 }
+
+impl std::fmt::Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::map::{Map, MapKey};
+    use super::Value;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_list_display() {
+        let inner = Value::List(Rc::new(RefCell::new(vec![
+            Value::String(String::from("a")),
+            Value::Null,
+        ])));
+        let list = Rc::new(RefCell::new(vec![Value::Int(1), inner]));
+        assert_eq!(
+            format!("{}", Value::List(list.clone())),
+            "[1, [\"a\", ghaib]]"
+        );
+        list.borrow_mut().push(Value::List(list.clone()));
+        assert_eq!(
+            format!("{}", Value::List(list.clone())),
+            "[1, [\"a\", ghaib], [...]]"
+        );
+        // break the cycle so the list is freed
+        list.borrow_mut().clear();
+    }
+
+    #[test]
+    fn test_map_display() {
+        let map = Rc::new(RefCell::new(Map::new()));
+        map.borrow_mut()
+            .insert(MapKey::String(String::from("a")), Value::Int(1));
+        map.borrow_mut()
+            .insert(MapKey::Int(2), Value::Map(map.clone()));
+        assert_eq!(
+            format!("{}", Value::Map(map.clone())),
+            "{\"a\": 1, 2: {...}}"
+        );
+        map.borrow_mut().remove(&MapKey::Int(2));
+    }
+}
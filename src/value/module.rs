@@ -0,0 +1,32 @@
+use super::Value;
+use crate::vm::table::Table;
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/// A file loaded by 'istawrid', holding the globals its top-level code defined.
+pub struct Module {
+    pub name: String,
+    pub globals: Rc<RefCell<Table>>,
+}
+
+impl Module {
+    pub fn new(name: String, globals: Rc<RefCell<Table>>) -> Self {
+        Self { name, globals }
+    }
+
+    /// Looks up an exported name. Names starting with `_` stay private to the module, and natives it can see
+    /// aren't its exports.
+    pub fn export(&self, name: &String) -> Option<Value> {
+        if name.starts_with('_') {
+            return None;
+        }
+        return self.globals.borrow().get_own(name);
+    }
+}
+
+impl Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<module {}>", self.name)
+    }
+}
@@ -0,0 +1,28 @@
+use super::Value;
+use crate::error::QalamError;
+use std::fmt::Display;
+
+pub type NativeFn = fn(&[Value]) -> Result<Value, QalamError>;
+
+/// A function implemented in Rust and exposed to scripts.
+pub struct Native {
+    pub name: String,
+    pub arity: usize,
+    pub function: NativeFn,
+}
+
+impl Native {
+    pub fn new(name: String, arity: usize, function: NativeFn) -> Self {
+        Self {
+            name,
+            arity,
+            function,
+        }
+    }
+}
+
+impl Display for Native {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native amal {}>", self.name)
+    }
+}
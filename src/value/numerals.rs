@@ -0,0 +1,76 @@
+use std::cell::Cell;
+
+/// Digits used when displaying numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Numerals {
+    /// `0123456789` and `.`
+    Western,
+    /// `٠١٢٣٤٥٦٧٨٩` and `٫`
+    ArabicIndic,
+}
+
+const ARABIC_INDIC_ZERO: u32 = 0x0660;
+const PERSIAN_ZERO: u32 = 0x06F0;
+pub const ARABIC_DECIMAL_SEPARATOR: char = '٫';
+
+thread_local! {
+    static NUMERALS: Cell<Numerals> = const { Cell::new(Numerals::Western) };
+}
+
+/// Selects the digits `Value`'s `Display` uses for numbers on this thread, returning the previous selection.
+pub fn set_numerals(numerals: Numerals) -> Numerals {
+    return NUMERALS.with(|current| current.replace(numerals));
+}
+
+/// The ASCII digit for a Western, Arabic-Indic or Persian digit.
+pub fn ascii_digit(c: char) -> Option<char> {
+    let code = c as u32;
+    let value = match c {
+        '0'..='9' => return Some(c),
+        '٠'..='٩' => code - ARABIC_INDIC_ZERO,
+        '۰'..='۹' => code - PERSIAN_ZERO,
+        _ => return None,
+    };
+    return char::from_digit(value, 10);
+}
+
+/// Rewrites a numeric literal into ASCII digits and `.` so it can be parsed.
+pub fn normalize(text: &str) -> String {
+    return text
+        .chars()
+        .map(|c| match c {
+            ARABIC_DECIMAL_SEPARATOR => '.',
+            c => ascii_digit(c).unwrap_or(c),
+        })
+        .collect();
+}
+
+/// Rewrites a formatted number into the selected digits.
+pub fn localize(text: String) -> String {
+    if NUMERALS.with(|current| current.get()) == Numerals::Western {
+        return text;
+    }
+    return text
+        .chars()
+        .map(|c| match c {
+            '.' => ARABIC_DECIMAL_SEPARATOR,
+            '0'..='9' => char::from_u32(ARABIC_INDIC_ZERO + c.to_digit(10).unwrap()).unwrap(),
+            c => c,
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{localize, normalize, set_numerals, Numerals};
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(normalize("١٢٫٥"), "12.5");
+        assert_eq!(normalize("۱۲۳"), "123");
+        set_numerals(Numerals::ArabicIndic);
+        assert_eq!(localize((-12.5).to_string()), "-١٢٫٥");
+        set_numerals(Numerals::Western);
+        assert_eq!(localize((-12.5).to_string()), "-12.5");
+    }
+}
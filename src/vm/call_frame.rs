@@ -0,0 +1,66 @@
+use crate::value::closure::{Closure, Upvalue};
+use crate::value::Value;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub const FRAMES_MAX: usize = 256;
+
+/// An active 'hawil' block of a frame.
+pub struct Handler {
+    /// Offset of the code that receives the error.
+    pub ip: usize,
+    /// Stack height when the block was entered; everything above it is discarded when an error reaches it.
+    pub stack_len: usize,
+}
+
+pub struct CallFrame {
+    pub closure: Rc<Closure>,
+    pub ip: usize,
+    /// Index of the frame's slot 0 on the value stack.
+    pub base: usize,
+    /// Upvalues still pointing at this frame's slots.
+    pub open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    /// Handlers of the 'hawil' blocks currently running in this frame, innermost last.
+    pub handlers: Vec<Handler>,
+}
+
+impl CallFrame {
+    pub fn new(closure: Rc<Closure>, base: usize) -> Self {
+        Self {
+            closure,
+            ip: 0,
+            base,
+            open_upvalues: Vec::new(),
+            handlers: Vec::new(),
+        }
+    }
+
+    /// Returns the upvalue for the absolute stack `slot`, reusing an open one if the slot was already captured.
+    pub fn capture_upvalue(&mut self, slot: usize) -> Rc<RefCell<Upvalue>> {
+        for upvalue in self.open_upvalues.iter() {
+            if let Upvalue::Open(open_slot) = *upvalue.borrow() {
+                if open_slot == slot {
+                    return upvalue.clone();
+                }
+            }
+        }
+        let upvalue = Rc::new(RefCell::new(Upvalue::Open(slot)));
+        self.open_upvalues.push(upvalue.clone());
+        return upvalue;
+    }
+
+    /// Closes every open upvalue at or above the absolute stack slot `from`, moving the value off the stack.
+    pub fn close_upvalues(&mut self, from: usize, stack: &Vec<Value>) {
+        self.open_upvalues.retain(|upvalue| {
+            let slot = match *upvalue.borrow() {
+                Upvalue::Open(slot) => slot,
+                Upvalue::Closed(_) => return false,
+            };
+            if slot < from {
+                return true;
+            }
+            upvalue.replace(Upvalue::Closed(stack[slot].clone()));
+            return false;
+        });
+    }
+}
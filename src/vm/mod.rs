@@ -1,31 +1,84 @@
+use crate::chunk::import::Import;
 use crate::chunk::Chunk;
+use crate::compiler::keywords::{Dialect, DEFAULT_DIALECTS};
 use crate::compiler::Compiler;
 use crate::error::QalamError;
+use crate::value::closure::Closure;
+use crate::value::exception::Exception;
+use crate::value::function::Function;
+use crate::value::module::Module;
+use crate::value::native::{Native, NativeFn};
+use crate::value::numerals::{self, Numerals};
 use crate::value::Value;
+use call_frame::CallFrame;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use table::Table;
+pub mod call_frame;
+pub mod native;
 pub mod table;
 
 pub struct VM {
     stack: Rc<RefCell<Vec<Value>>>,
-    call_frame: Rc<RefCell<Vec<String>>>,
+    call_frame: Rc<RefCell<Vec<CallFrame>>>,
     globals: Rc<RefCell<Table>>,
-    ip: RefCell<usize>,
+    /// Natives every module can read, without them becoming its exports.
+    natives: Rc<RefCell<Table>>,
+    dialects: Vec<Dialect>,
+    numerals: Numerals,
+    /// Path of the script being run, which relative imports start from.
+    script_path: Option<PathBuf>,
+    /// Directories searched for imports that aren't relative.
+    search_paths: Vec<PathBuf>,
+    /// Loaded modules by canonical path, so each file runs once.
+    modules: HashMap<PathBuf, Rc<Module>>,
+    /// Canonical paths of the files whose top-level code is running, outermost first.
+    loading: Vec<PathBuf>,
+    /// Compiler warnings from the last `interpret`, including its imports.
+    warnings: Vec<QalamError>,
+}
+
+fn file_name(path: &Path) -> String {
+    return path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
 }
 
 impl VM {
     pub fn new() -> Self {
-        return Self {
+        let mut vm = Self {
             stack: Rc::new(RefCell::new(Vec::new())),
             call_frame: Rc::new(RefCell::new(Vec::new())),
             globals: Rc::new(RefCell::new(Table::new())),
-            ip: RefCell::new(0),
+            natives: Rc::new(RefCell::new(Table::new())),
+            dialects: DEFAULT_DIALECTS.to_vec(),
+            numerals: Numerals::Western,
+            script_path: None,
+            search_paths: Vec::new(),
+            modules: HashMap::new(),
+            loading: Vec::new(),
+            warnings: Vec::new(),
         };
+        vm.define_native("clock", 0, native::clock);
+        vm.define_native("delete", 2, native::delete);
+        vm.define_native("has", 2, native::has);
+        return vm;
+    }
+
+    /// Exposes a Rust function to scripts as the global `name`.
+    pub fn define_native(&mut self, name: &str, arity: usize, function: NativeFn) {
+        let native = Value::Native(Rc::new(Native::new(name.to_string(), arity, function)));
+        self.globals
+            .borrow_mut()
+            .add(name.to_string(), native.clone());
+        self.natives.borrow_mut().add(name.to_string(), native);
     }
 
     #[allow(unused)]
-    fn debug(&self, chunk: &mut Chunk) {
+    fn debug(&self, chunk: &Chunk, ip: usize) {
         // print!("          ");
         for value in self.stack.borrow().iter() {
             print!("[ ");
@@ -34,56 +87,505 @@ impl VM {
         }
         print!("\n");
         // println!("{}", self.globals.borrow());
-        print!("{}\n", chunk.code[*self.ip.borrow()]);
+        print!("{}\n", chunk.code[ip]);
+    }
+
+    /// Selects the keyword dialects recognised by later `interpret` calls.
+    pub fn set_dialects(&mut self, dialects: &[Dialect]) {
+        self.dialects = dialects.to_vec();
+    }
+
+    /// Selects the digits numbers are displayed with while this VM runs.
+    pub fn set_numerals(&mut self, numerals: Numerals) {
+        self.numerals = numerals;
+    }
+
+    /// Sets the file being run, so its imports resolve relative to it.
+    pub fn set_script_path(&mut self, path: &Path) {
+        self.script_path = Some(path.to_path_buf());
+    }
+
+    /// Adds a directory to search for imports that aren't relative to the importing file.
+    pub fn add_search_path(&mut self, path: &Path) {
+        self.search_paths.push(path.to_path_buf());
     }
 
     pub fn interpret(&mut self, src: Vec<u8>) -> Result<(), QalamError> {
-        let mut chunk = Compiler::compile(src)?;
-        println!("{}", chunk);
+        let file = match &self.script_path {
+            Some(path) => file_name(path),
+            None => String::from("script"),
+        };
+        self.warnings.clear();
+        let mut function = Compiler::compile_file(src, &self.dialects, &file)?;
+        self.warnings.append(&mut function.warnings);
+        println!("{}", function.chunk);
         // return Ok(());
-        self.run(&mut chunk)
+        self.run(Rc::new(function))
+    }
+
+    pub fn warnings(&self) -> &Vec<QalamError> {
+        return &self.warnings;
+    }
+
+    pub fn run(&mut self, function: Rc<Function>) -> Result<(), QalamError> {
+        // other VMs on this thread keep their own numerals
+        let previous = numerals::set_numerals(self.numerals);
+        let result = self.run_script(function);
+        numerals::set_numerals(previous);
+        return result;
     }
 
-    pub fn run(&mut self, chunk: &mut Chunk) -> Result<(), QalamError> {
-        let n = chunk.code.len();
-        // temporarily adding this in manually, later on it should be a generic Function that has this already dealt with
-        self.call_frame.borrow_mut().push(String::from("__main__"));
-        let call_frame_size = self.call_frame.borrow().len();
+    fn run_script(&mut self, function: Rc<Function>) -> Result<(), QalamError> {
+        // the script occupies slot 0 of its own frame, like any other function
+        let base = self.stack.borrow().len();
+        let closure = Rc::new(Closure::new(function, Vec::new(), self.globals.clone()));
+        self.stack
+            .borrow_mut()
+            .push(Value::Closure(closure.clone()));
+        self.call_frame
+            .borrow_mut()
+            .push(CallFrame::new(closure, base));
+        self.loading = self
+            .script_path
+            .iter()
+            .filter_map(|path| path.canonicalize().ok())
+            .collect();
+        if let Err(e) = self.execute(0) {
+            self.stack.borrow_mut().clear();
+            self.call_frame.borrow_mut().clear();
+            return Err(e);
+        }
+        // discard the script's return value
+        self.stack.borrow_mut().pop();
+
+        return Ok(());
+    }
+
+    /// Runs instructions until the call stack shrinks back to `floor` frames.
+    fn execute(&mut self, floor: usize) -> Result<(), QalamError> {
         loop {
-            if *self.ip.borrow() >= n {
+            let (closure, ip, depth) = {
+                let frames = self.call_frame.borrow();
+                if frames.len() <= floor {
+                    break;
+                }
+                let frame = frames.last().unwrap();
+                (frame.closure.clone(), frame.ip, frames.len())
+            };
+            let function = &closure.function;
+            if ip >= function.chunk.code.len() {
                 break;
             }
-            // self.debug(chunk);
-            let inst = &chunk.code[*self.ip.borrow()];
-            let line = &chunk.lines[*self.ip.borrow()];
-            let offset = inst.eval(
-                *self.ip.borrow(),
-                self.stack.clone(),
-                self.call_frame.clone(),
-                self.globals.clone(),
-                *line,
-            );
+            // self.debug(&function.chunk, ip);
+            let inst = &function.chunk.code[ip];
+            let line = function.chunk.lines[ip];
+            let offset = match inst.as_any().downcast_ref::<Import>() {
+                Some(import) => self.import(&import.path, ip, line),
+                None => inst.eval(
+                    ip,
+                    self.stack.clone(),
+                    self.call_frame.clone(),
+                    closure.globals.clone(),
+                    line,
+                ),
+            };
             match offset {
                 Ok(offset) => {
-                    if offset > 0 {
-                        self.ip.replace(offset);
-                    } else {
-                        self.ip.replace_with(|&mut old| old + 1);
-                    }
-                    if self.call_frame.borrow().len() < call_frame_size {
-                        // hit return statement
-                        let val = self.stack.borrow_mut().pop();
-                        if let Some(val) = val {
-                            print!("{}\n", val);
-                        }
+                    // a call pushes a new frame, a return pops one; the frame that ran the instruction resumes at `offset`
+                    if let Some(frame) = self.call_frame.borrow_mut().get_mut(depth - 1) {
+                        frame.ip = offset;
                     }
                 }
                 Err(e) => {
+                    if self.unwind(&e, floor) {
+                        continue;
+                    }
                     return Err(e);
                 }
             }
         }
-
         return Ok(());
     }
+
+    /// Finds the file an import refers to: paths starting with `.` are relative to the importing file, others are also looked up in the search paths.
+    fn resolve(&self, path: &str, line: usize) -> Result<PathBuf, QalamError> {
+        let importer = match self.loading.last() {
+            Some(importer) => importer.parent().map(Path::to_path_buf),
+            None => None,
+        };
+        let mut candidates = vec![importer.unwrap_or_default().join(path)];
+        if !path.starts_with('.') {
+            candidates.extend(self.search_paths.iter().map(|dir| dir.join(path)));
+        }
+        for candidate in candidates {
+            if let Ok(found) = candidate.canonicalize() {
+                return Ok(found);
+            }
+        }
+        return Err(QalamError::with_line_runtime(
+            &format!("Module '{}' not found.", path),
+            line,
+        ));
+    }
+
+    /// Pushes the module at `path`, compiling and running it the first time it is imported.
+    fn import(&mut self, path: &str, ip: usize, line: usize) -> Result<usize, QalamError> {
+        let path = self.resolve(path, line)?;
+        if let Some(module) = self.modules.get(&path) {
+            self.stack.borrow_mut().push(Value::Module(module.clone()));
+            return Ok(ip + 1);
+        }
+        if let Some(start) = self.loading.iter().position(|loading| *loading == path) {
+            let chain = self.loading[start..]
+                .iter()
+                .chain([&path])
+                .map(|file| file.display().to_string())
+                .collect::<Vec<String>>();
+            return Err(QalamError::with_line_runtime(
+                &format!("Circular import: {}.", chain.join(" -> ")),
+                line,
+            ));
+        }
+
+        let mut src = std::fs::read(&path).map_err(|e| {
+            QalamError::with_line_runtime(
+                &format!("Can't read module '{}': {}", path.display(), e),
+                line,
+            )
+        })?;
+        src.push(b'\n');
+        let mut function = Compiler::compile_file(src, &self.dialects, &file_name(&path))?;
+        self.warnings.append(&mut function.warnings);
+        let globals = Rc::new(RefCell::new(Table::with_fallback(self.natives.clone())));
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let module = Rc::new(Module::new(name, globals.clone()));
+
+        // run the module's top-level code as a call, on top of the importing frame
+        let floor = self.call_frame.borrow().len();
+        let base = self.stack.borrow().len();
+        let closure = Rc::new(Closure::new(Rc::new(function), Vec::new(), globals));
+        self.stack
+            .borrow_mut()
+            .push(Value::Closure(closure.clone()));
+        self.call_frame
+            .borrow_mut()
+            .push(CallFrame::new(closure, base));
+        self.loading.push(path.clone());
+        let result = self.execute(floor);
+        self.loading.pop();
+        // drop the module's return value, or whatever an error left behind
+        self.stack.borrow_mut().truncate(base);
+        result?;
+
+        self.modules.insert(path, module.clone());
+        self.stack.borrow_mut().push(Value::Module(module));
+        return Ok(ip + 1);
+    }
+
+    /// Hands `error` to the innermost 'hawil' handler above `floor` frames, popping the frames above it. Returns false if nothing handles it.
+    fn unwind(&self, error: &QalamError, floor: usize) -> bool {
+        let value = error
+            .thrown_value()
+            .unwrap_or_else(|| Value::Error(Rc::new(Exception::new(error))));
+        let mut frames = self.call_frame.borrow_mut();
+        while frames.len() > floor {
+            let frame = frames.last_mut().unwrap();
+            if let Some(handler) = frame.handlers.pop() {
+                frame.close_upvalues(handler.stack_len, &self.stack.borrow());
+                self.stack.borrow_mut().truncate(handler.stack_len);
+                self.stack.borrow_mut().push(value);
+                frame.ip = handler.ip;
+                return true;
+            }
+            frame.close_upvalues(frame.base, &self.stack.borrow());
+            frames.pop();
+        }
+        return false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::QalamError;
+    use crate::value::numerals::{self, Numerals};
+    use crate::value::Value;
+    use crate::vm::VM;
+
+    fn fail(_: &[Value]) -> Result<Value, QalamError> {
+        return Err(QalamError::new_runtime("Host failure."));
+    }
+
+    fn double(args: &[Value]) -> Result<Value, QalamError> {
+        match args[0] {
+            Value::Number(n) => return Ok(Value::Number(n * 2.0)),
+            Value::Int(n) => return Ok(Value::Int(n * 2)),
+            _ => return Err(QalamError::new_runtime("Expected a number.")),
+        }
+    }
+
+    #[test]
+    fn test_define_native() {
+        let mut vm = VM::new();
+        vm.define_native("double", 1, double);
+        vm.define_native("fail", 0, fail);
+        let src = "itha (double(21) != 42) { fail(); }\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm.interpret(Vec::<u8>::from("\nfail();\n")).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "RuntimeError: Host failure.\n\tat line 2"
+        );
+    }
+
+    #[test]
+    fn test_numerals_restored() {
+        let mut vm = VM::new();
+        vm.set_numerals(Numerals::ArabicIndic);
+        assert!(vm.interpret(Vec::<u8>::from("qul 12;\n")).is_ok());
+        assert_eq!(numerals::localize(String::from("12")), "12");
+    }
+
+    #[test]
+    fn test_closures() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        // upvalue outlives the frame that declared it
+        let src = "amal counter() { shai n = 0; amal inc() { n = n + 1; radd n; } radd inc; }\n\
+                   shai c = counter(); c();\nitha (c() != 2) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        // two closures share one upvalue closed by end_scope
+        let src = "shai get; shai set;\n\
+                   { shai v = 1; amal g() { radd v; } amal s(x) { v = x; } get = g; set = s; }\n\
+                   set(5);\nitha (get() != 5) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        // each loop iteration closes over its own variable
+        let src = "shai fs = [ghaib, ghaib, ghaib]; shai i = 0;\n\
+                   baynama (i < 3) { shai j = i; amal f() { radd j; } fs[i] = f; i = i + 1; }\n\
+                   itha (fs[0]() != 0 aw fs[2]() != 2) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_classes() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "kitab P {\n\
+                   init(x) { nafs.x = x; }\n\
+                   get() { radd nafs.x; }\n\
+                   add(d) { nafs.x = nafs.x + d; radd nafs; }\n\
+                   }\n\
+                   shai p = P(3);\nitha (p.get() != 3) fail();\n\
+                   shai g = p.get; p.x = 7;\nitha (g() != 7) fail();\n\
+                   itha (p.add(1).add(2).x != 10) fail();\n\
+                   p.y = \"f\";\nitha (p.y != \"f\") fail();\n\
+                   itha (P(1).init(9).x != 9) fail();\n\
+                   kitab Q ibn P { get() { radd ulya.get() * 2; } }\n\
+                   itha (Q(4).get() != 8) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm
+            .interpret(Vec::<u8>::from("kitab A {}\nA(1);\n"))
+            .unwrap_err();
+        assert!(format!("{}", err).contains("at line 2"));
+    }
+
+    #[test]
+    fn test_lists() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "shai xs = [[1, 2], [3, \"4\"]];\nxs[1][0] += 4;\n\
+                   itha (xs[1][0] != 7 aw xs[0] != [1, 2]) fail();\n\
+                   shai l = [0]; l[0] = l;\nqul l;\nitha (l != l aw l[0][0] != l) fail();\n\
+                   shai a = [1]; a[0] = a; shai b = [1]; b[0] = b;\nitha (a != b) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm
+            .interpret(Vec::<u8>::from("shai xs = [1, 2];\nxs[2];\n"))
+            .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "RuntimeError: List index 2 out of bounds for length 2.\n\tat line 2"
+        );
+        let err = vm
+            .interpret(Vec::<u8>::from("[1][2 ** 80];\n"))
+            .unwrap_err();
+        assert!(format!("{}", err)
+            .starts_with("RuntimeError: List index 1208925819614629174706176 out of bounds"));
+    }
+
+    #[test]
+    fn test_maps() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "shai m = {}; m[\"s\"] = m;\nqul m;\nitha (m != m aw m[\"s\"] != m) fail();\n\
+                   shai a = {\"k\": [1]}; a[\"k\"][0] = a;\n\
+                   shai b = {\"k\": [1]}; b[\"k\"][0] = b;\nitha (a != b) fail();\n\
+                   itha ({\"x\": 1} == {\"y\": 1}) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_pow() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "itha (2 ** 3 ** 2 != 512 aw 2 ** -1 != 0.5) fail();\n\
+                   itha (-2 ** 2 != -4 aw (-2) ** 2 != 4 aw 2 ** -1 ** 2 != 0.5) fail();\n\
+                   itha (~2 ** 2 != -5 aw -2 * 3 != -6) fail();\n\
+                   shai x = 3; x **= 2;\nitha (x != 9) fail();\n\
+                   { shai y = 2; y **= 3; itha (y != 8) fail(); }\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "itha (6 & 3 | 8 != 10 aw 1 | 2 ^ 3 != 1 aw 1 << 2 + 1 != 8) fail();\n\
+                   itha (~5 != -6 aw ~~5 != 5) fail();\n\
+                   shai b = 12;\nb &= 10; itha (b != 8) fail();\nb |= 1; itha (b != 9) fail();\n\
+                   b ^= 3; itha (b != 10) fail();\nb <<= 2; itha (b != 40) fail();\n\
+                   b >>= 1; itha (b != 20) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_conditional() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "itha ((haqq ? 1 : fail()) != 1) fail();\n\
+                   itha ((batil ? fail() : 2) != 2) fail();\n\
+                   itha ((ghaib ? fail() : batil ? fail() : 3) != 3) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_null_operators() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "itha ((1 ?? fail()) != 1 aw (ghaib ?? 2) != 2) fail();\n\
+                   shai f = ghaib;\nitha (f?.(fail()) != ghaib) fail();\n\
+                   shai m = {}; m[\"k\"] ??= 3; m[\"k\"] ??= fail();\n\
+                   itha (m[\"k\"] != 3) fail();\n\
+                   shai x; x ??= 1; x ??= fail();\nitha (x != 1) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm
+            .interpret(Vec::<u8>::from("shai m = {};\nm[\"k\"] += 1;\n"))
+            .unwrap_err();
+        assert!(format!("{}", err).starts_with("RuntimeError: Key \"k\" not found in map."));
+    }
+
+    #[test]
+    fn test_match() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "amal kind(v) {\n\
+                   tabiq (v) {\n\
+                   0 => radd \"zero\";\n\
+                   1..=9 => radd \"digit\";\n\
+                   \"s\" => radd \"string\";\n\
+                   n itha n < 0 => radd -n;\n\
+                   n itha n > 100 => radd \"big\";\n\
+                   _ => radd \"other\";\n\
+                   }\n\
+                   }\n\
+                   itha (kind(0) != \"zero\" aw kind(9) != \"digit\" aw kind(\"s\") != \"string\") fail();\n\
+                   itha (kind(-4) != 4 aw kind(101) != \"big\" aw kind(50) != \"other\") fail();\n\
+                   itha (kind(9.5) != \"other\") fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        assert!(vm.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_for_in() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "shai sum = 0; tawaf (shai i fi 1..4) sum += i;\nitha (sum != 6) fail();\n\
+                   sum = 0; tawaf (shai i fi 1..=4) sum += i;\nitha (sum != 10) fail();\n\
+                   shai s = \"\"; tawaf (shai c fi \"سلام\") s = c + s;\nitha (s != \"مالس\") fail();\n\
+                   shai keys = \"\"; tawaf (shai k fi {\"a\": 1, \"b\": 2}) keys += k;\n\
+                   itha (keys != \"ab\") fail();\n\
+                   sum = 0; tawaf (shai x fi [1, 2, 3]) { itha (x == 2) safar; sum += x; }\n\
+                   itha (sum != 4) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm
+            .interpret(Vec::<u8>::from("tawaf (shai x fi 1) {}\n"))
+            .unwrap_err();
+        assert!(format!("{}", err)
+            .starts_with("RuntimeError: Can only iterate over lists, strings, maps and ranges."));
+    }
+
+    #[test]
+    fn test_lambdas() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "shai add = (a, b) => a + b;\nitha (add(1, 2) != 3) fail();\n\
+                   shai sq = amal(x) { radd x * x; };\nitha (sq(4) != 16) fail();\n\
+                   amal ok(f, v) { radd f(v); }\n\
+                   shai hit = batil;\n\
+                   tabiq (1) { n itha ok((v) => v == n, 1) => hit = haqq; _ => fail(); }\n\
+                   tabiq (2) { n itha (n) => {} _ => fail(); }\n\
+                   tabiq (3) { n itha [(v) => v][0](n) == 3 => {} _ => fail(); }\n\
+                   itha (!hit) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+    }
+
+    #[test]
+    fn test_try_catch() {
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        let src = "hawil { fail(); } imsak (e) { itha (e.line != 1) fail(); }\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        // a throw from 'imsak' must leave the stack as the compiler expects for each way out of 'akhiran'
+        let src = "amal brk() {\n\
+                   baynama (haqq) { hawil { irmi 1; } imsak (e) { irmi 2; } akhiran { iftar; } }\n\
+                   shai x = \"x\"; radd x;\n\
+                   }\n\
+                   itha (brk() != \"x\") fail();\n\
+                   amal cont() {\n\
+                   shai n = 0;\n\
+                   baynama (n < 3) { n += 1; hawil { irmi 1; } imsak (e) { irmi 2; } akhiran { safar; } }\n\
+                   shai y = 5; radd y + n;\n\
+                   }\n\
+                   itha (cont() != 8) fail();\n\
+                   amal ret() { shai a = 1; hawil { irmi 1; } imsak (e) { irmi 2; } akhiran { radd a; } }\n\
+                   itha (ret() != 1) fail();\n\
+                   hawil { hawil { irmi 1; } imsak (e) { irmi 2; } } imsak (e) { itha (e != 2) fail(); }\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let src = "amal f() { hawil { radd 1; } akhiran { irmi \"x\"; } }\nf();\n";
+        let err = vm.interpret(Vec::<u8>::from(src)).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "RuntimeError: Uncaught \"x\".\n\tat line 1"
+        );
+    }
+
+    #[test]
+    fn test_import() {
+        let dir = std::env::temp_dir().join(format!("rqalam-import-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.q"), "shai n = 0;\nn = n + clock() * 0 + 1;\n").unwrap();
+        std::fs::write(dir.join("a.q"), "istawrid \"./b.q\";\n").unwrap();
+        std::fs::write(dir.join("b.q"), "istawrid \"./a.q\";\n").unwrap();
+        std::fs::write(dir.join("main.q"), "").unwrap();
+
+        let mut vm = VM::new();
+        vm.define_native("fail", 0, fail);
+        vm.set_script_path(&dir.join("main.q"));
+        let src =
+            "istawrid \"lib.q\";\nistawrid \"./lib.q\" bism again;\nitha (again.n != 1) fail();\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        // natives the module can call aren't its exports
+        let src = "istawrid \"lib.q\";\n\
+                   hawil { lib.clock; fail(); } imsak (e) {\n\
+                   itha (e.message != \"Module 'lib' has no export 'clock'.\") fail();\n\
+                   }\n";
+        assert!(vm.interpret(Vec::<u8>::from(src)).is_ok());
+        let err = vm
+            .interpret(Vec::<u8>::from("istawrid \"a.q\";\n"))
+            .unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(format!("{}", err).starts_with("RuntimeError: Circular import:"));
+        assert!(format!("{}", err).contains("b.q ->"));
+    }
 }
@@ -0,0 +1,34 @@
+use crate::error::QalamError;
+use crate::value::map::MapKey;
+use crate::value::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seconds since the unix epoch.
+pub fn clock(_: &[Value]) -> Result<Value, QalamError> {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => return Ok(Value::Number(duration.as_secs_f64())),
+        Err(e) => return Err(QalamError::new_runtime(&format!("{}", e))),
+    }
+}
+
+/// Removes `key` from a map, returning the removed value or 'ghaib' if it was absent.
+pub fn delete(args: &[Value]) -> Result<Value, QalamError> {
+    match &args[0] {
+        Value::Map(map) => {
+            let key = MapKey::from_value(&args[1])?;
+            return Ok(map.borrow_mut().remove(&key).unwrap_or(Value::Null));
+        }
+        _ => return Err(QalamError::new_runtime("delete() expects a map.")),
+    }
+}
+
+/// Whether a map contains `key`.
+pub fn has(args: &[Value]) -> Result<Value, QalamError> {
+    match &args[0] {
+        Value::Map(map) => {
+            let key = MapKey::from_value(&args[1])?;
+            return Ok(Value::Bool(map.borrow().get(&key).is_some()));
+        }
+        _ => return Err(QalamError::new_runtime("has() expects a map.")),
+    }
+}
@@ -1,14 +1,26 @@
 use super::Value;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 pub struct Table {
     map: HashMap<String, Value>,
+    /// Read when a name isn't in this table, but never written through.
+    fallback: Option<Rc<RefCell<Table>>>,
 }
 
 impl Table {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    pub fn with_fallback(fallback: Rc<RefCell<Table>>) -> Self {
+        Self {
+            map: HashMap::new(),
+            fallback: Some(fallback),
         }
     }
 
@@ -17,12 +29,26 @@ impl Table {
     }
 
     pub fn get(&self, id: &String) -> Option<Value> {
+        if let Some(val) = self.get_own(id) {
+            return Some(val);
+        }
+        return self.fallback.as_ref()?.borrow().get(id);
+    }
+
+    /// Looks up `id` without consulting the fallback.
+    pub fn get_own(&self, id: &String) -> Option<Value> {
         if self.map.contains_key(id) {
             return Some(self.map.get(id).unwrap().clone());
         }
         return None;
     }
 
+    pub fn add_all(&mut self, other: &Table) {
+        for (key, value) in other.map.iter() {
+            self.map.insert(key.clone(), value.clone());
+        }
+    }
+
     pub fn overwrite(&mut self, id: String, val: Value) -> Option<Value> {
         if self.map.contains_key(&id) {
             return Some(self.map.insert(id, val).unwrap().clone());